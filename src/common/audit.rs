@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use std::path::PathBuf;
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub timestamp: String,
+    pub request_id: Option<String>,
+    pub action: String,
+    pub target: String,
+    pub destination: Option<String>,
+    pub dry_run: bool,
+    pub outcome: AuditOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "status", content = "error", rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    /// The operation was checked but not performed.
+    DryRun,
+    Failure(String),
+}
+
+impl AuditEntry {
+    pub fn new(action: &str, target: &str) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            request_id: None,
+            action: action.to_string(),
+            target: target.to_string(),
+            destination: None,
+            dry_run: false,
+            outcome: AuditOutcome::Success,
+        }
+    }
+}
+
+/// Records administrative operations, one entry per operation.
+#[async_trait]
+pub trait AuditLog: Send + Sync {
+    async fn record(&self, entry: AuditEntry);
+}
+
+/// Audit trail emitted on the `audit` tracing target and, when `AUDIT_LOG_PATH`
+/// is set, appended as JSON lines to that file.
+pub struct JsonlAuditLog {
+    path: Option<PathBuf>,
+    write_lock: Mutex<()>,
+}
+
+impl JsonlAuditLog {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    async fn append(&self, line: &str) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let _guard = self.write_lock.lock().await;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        file.write_all(format!("{}\n", line).as_bytes()).await?;
+        file.flush().await
+    }
+}
+
+#[async_trait]
+impl AuditLog for JsonlAuditLog {
+    async fn record(&self, entry: AuditEntry) {
+        let line = serde_json::to_string(&entry).expect("audit entry serializes to JSON");
+        tracing::info!(target: "audit", entry = %line, "Admin operation");
+
+        if let Err(e) = self.append(&line).await {
+            tracing::error!("Failed to write audit log entry: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_entries_are_appended_as_json_lines() {
+        let path = std::env::temp_dir().join(format!("audit-{}.jsonl", uuid::Uuid::new_v4()));
+        let log = JsonlAuditLog::new(Some(path.clone()));
+
+        log.record(AuditEntry::new("delete", "videos/old.mp4"))
+            .await;
+        log.record(AuditEntry {
+            outcome: AuditOutcome::Failure("boom".to_string()),
+            ..AuditEntry::new("copy", "videos/a.mp4")
+        })
+        .await;
+        log.record(AuditEntry {
+            dry_run: true,
+            outcome: AuditOutcome::DryRun,
+            ..AuditEntry::new("move", "videos/b.mp4")
+        })
+        .await;
+
+        let content = tokio::fs::read_to_string(&path).await.unwrap();
+        let _ = tokio::fs::remove_file(&path).await;
+
+        let lines = content
+            .lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["action"], "delete");
+        assert_eq!(lines[0]["outcome"]["status"], "success");
+        assert_eq!(lines[1]["outcome"]["error"], "boom");
+        assert_eq!(lines[2]["outcome"]["status"], "dry_run");
+    }
+}
@@ -1,16 +1,54 @@
 use anyhow::Result;
 use dotenvy::dotenv;
-use std::{env, sync::Arc};
+use ipnet::IpNet;
+use std::{env, net::IpAddr, path::PathBuf, sync::Arc, time::Duration};
 
-use crate::common::infrastructure::storage::StorageConfig;
+use crate::common::infrastructure::storage::{S3CompatibleConfig, StorageConfig};
+use crate::common::middleware::{RateLimitRule, TrustedProxies};
+use crate::domains::bundle::BundleManifest;
+use crate::domains::contact::captcha::{HCAPTCHA_VERIFY_URL, TURNSTILE_VERIFY_URL};
+use crate::domains::contact::pow::MAX_DIFFICULTY;
+use crate::domains::contact::service::{
+    DISCORD_API_BASE_URL, DeliveryPolicy, DiscordConfig, DiscordWebhookConfig, NotifierConfig,
+    SmtpConfig, SmtpTls, WebhookConfig, WebhookEndpoint,
+};
+use crate::domains::contact::{
+    CaptchaConfig, DuplicateConfig, FormGuardConfig, OutboxConfig, PowConfig, SpamConfig,
+};
+
+const DEFAULT_RATE_LIMITS: &str = "/api/v1/contact=5/600,/api/v1/contact/*=30/60,\
+    /api/v1/upload/*=10/600,/api/v1/video*=120/60,/api/v1/bundle/*=30/60";
 
 #[derive(Clone)]
 pub struct AppConfig {
     pub port: u16,
     pub allowed_origins: Vec<String>,
-    pub discord_bot_token: String,
-    pub discord_user_ids: Vec<String>,
+    pub notifiers: Vec<NotifierConfig>,
+    pub notifier_policy: DeliveryPolicy,
+    pub notification_templates_dir: Option<PathBuf>,
+    pub notification_locale: String,
+    pub outbox_path: PathBuf,
+    pub outbox: OutboxConfig,
     pub storage_config: Arc<StorageConfig>,
+    pub secondary_storage_config: Option<Arc<S3CompatibleConfig>>,
+    pub storage_health_check_interval_secs: u64,
+    pub admin_api_key: Option<String>,
+    pub signing_secret: Option<String>,
+    pub bundle_manifest: Arc<BundleManifest>,
+    pub audit_log_path: Option<PathBuf>,
+    pub upload_key_prefix: String,
+    pub upload_allowed_content_types: Vec<String>,
+    pub upload_max_size_bytes: u64,
+    pub form_guard: FormGuardConfig,
+    pub pow: PowConfig,
+    pub captcha: Option<CaptchaConfig>,
+    pub duplicates: DuplicateConfig,
+    pub email_blocklist_path: Option<PathBuf>,
+    pub email_blocklist_reload_interval: Duration,
+    pub spam: SpamConfig,
+    pub rate_limits: Vec<RateLimitRule>,
+    pub trusted_proxies: TrustedProxies,
+    pub idempotency_ttl: Duration,
 }
 
 impl AppConfig {
@@ -27,15 +65,50 @@ impl AppConfig {
             .map(|s| s.trim().to_string())
             .collect();
 
-        let discord_bot_token = env::var("DISCORD_BOT_TOKEN")
-            .map_err(|_| anyhow::anyhow!("DISCORD_BOT_TOKEN must be set"))?;
-
-        let discord_user_ids = env::var("DISCORD_USER_IDS")
-            .map_err(|_| anyhow::anyhow!("DISCORD_USER_IDS must be set"))?
+        // Several channels can be combined, e.g. `NOTIFIER=discord,smtp`
+        let notifiers = env::var("NOTIFIER")
+            .unwrap_or_else(|_| "discord".to_string())
             .split(',')
-            .map(|s| s.trim().to_string())
+            .map(|s| s.trim())
             .filter(|s| !s.is_empty())
-            .collect();
+            .map(notifier_config_from_env)
+            .collect::<Result<Vec<_>>>()?;
+
+        let notifier_policy = env::var("NOTIFIER_POLICY")
+            .unwrap_or_else(|_| "any".to_string())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("NOTIFIER_POLICY: {}", e))?;
+
+        let notification_templates_dir = env::var("NOTIFICATION_TEMPLATES_DIR")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from);
+
+        let notification_locale =
+            env::var("NOTIFICATION_LOCALE").unwrap_or_else(|_| "fr".to_string());
+
+        let outbox_path = env::var("OUTBOX_PATH")
+            .unwrap_or_else(|_| "outbox.sqlite3".to_string())
+            .into();
+
+        let outbox = OutboxConfig {
+            max_attempts: env::var("OUTBOX_MAX_ATTEMPTS")
+                .map(|v| v.parse())
+                .unwrap_or(Ok(8))?,
+            retry_base_delay: Duration::from_secs(
+                env::var("OUTBOX_RETRY_BASE_DELAY_SECS")
+                    .map(|v| v.parse())
+                    .unwrap_or(Ok(30))?,
+            ),
+            poll_interval: Duration::from_secs(
+                env::var("OUTBOX_POLL_INTERVAL_SECS")
+                    .map(|v| v.parse())
+                    .unwrap_or(Ok(5))?,
+            ),
+        };
+        if outbox.poll_interval.is_zero() {
+            anyhow::bail!("OUTBOX_POLL_INTERVAL_SECS must be greater than 0");
+        }
 
         let r2_account_id =
             env::var("R2_ACCOUNT_ID").map_err(|_| anyhow::anyhow!("R2_ACCOUNT_ID must be set"))?;
@@ -49,17 +122,399 @@ impl AppConfig {
         let r2_bucket_name = env::var("R2_BUCKET_NAME")
             .map_err(|_| anyhow::anyhow!("R2_BUCKET_NAME must be set"))?;
 
+        let secondary_storage_config = match env::var("STORAGE_SECONDARY_ENDPOINT") {
+            Ok(endpoint_url) => Some(Arc::new(S3CompatibleConfig {
+                endpoint_url,
+                region: env::var("STORAGE_SECONDARY_REGION").unwrap_or_else(|_| "auto".to_string()),
+                access_key_id: env::var("STORAGE_SECONDARY_ACCESS_KEY_ID")
+                    .map_err(|_| anyhow::anyhow!("STORAGE_SECONDARY_ACCESS_KEY_ID must be set"))?,
+                secret_access_key: env::var("STORAGE_SECONDARY_SECRET_ACCESS_KEY").map_err(
+                    |_| anyhow::anyhow!("STORAGE_SECONDARY_SECRET_ACCESS_KEY must be set"),
+                )?,
+                bucket_name: env::var("STORAGE_SECONDARY_BUCKET_NAME")
+                    .map_err(|_| anyhow::anyhow!("STORAGE_SECONDARY_BUCKET_NAME must be set"))?,
+            })),
+            Err(_) => None,
+        };
+
+        let storage_health_check_interval_secs = env::var("STORAGE_HEALTH_CHECK_INTERVAL_SECS")
+            .map(|v| v.parse())
+            .unwrap_or(Ok(30))?;
+        if storage_health_check_interval_secs == 0 {
+            anyhow::bail!("STORAGE_HEALTH_CHECK_INTERVAL_SECS must be greater than 0");
+        }
+
+        let admin_api_key = env::var("ADMIN_API_KEY").ok().filter(|s| !s.is_empty());
+
+        let signing_secret = env::var("SIGNING_SECRET").ok().filter(|s| !s.is_empty());
+
+        let bundle_manifest = match env::var("BUNDLE_MANIFEST_PATH") {
+            Ok(path) => BundleManifest::load(&PathBuf::from(path))?,
+            Err(_) => BundleManifest::default(),
+        };
+
+        let audit_log_path = env::var("AUDIT_LOG_PATH").ok().map(PathBuf::from);
+
+        let upload_key_prefix =
+            env::var("UPLOAD_KEY_PREFIX").unwrap_or_else(|_| "uploads/briefs/".to_string());
+
+        let upload_allowed_content_types = env::var("UPLOAD_ALLOWED_CONTENT_TYPES")
+            .unwrap_or_else(|_| "image/,video/,application/pdf".to_string())
+            .split(',')
+            .map(|s| s.trim().to_ascii_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let upload_max_size_bytes = env::var("UPLOAD_MAX_SIZE_BYTES")
+            .map(|v| v.parse())
+            .unwrap_or(Ok(50 * 1024 * 1024))?;
+
+        let form_guard = FormGuardConfig {
+            min_fill_time: Duration::from_millis(
+                env::var("CONTACT_MIN_FILL_TIME_MS")
+                    .map(|v| v.parse())
+                    .unwrap_or(Ok(3000))?,
+            ),
+            token_ttl: Duration::from_secs(
+                env::var("CONTACT_FORM_TOKEN_TTL_SECS")
+                    .map(|v| v.parse())
+                    .unwrap_or(Ok(2 * 3600))?,
+            ),
+            require_token: env::var("CONTACT_REQUIRE_FORM_TOKEN")
+                .map(|v| v.parse())
+                .unwrap_or(Ok(false))?,
+        };
+
+        let pow = PowConfig {
+            required: env::var("CONTACT_POW_REQUIRED")
+                .map(|v| v.parse())
+                .unwrap_or(Ok(false))?,
+            base_difficulty: env::var("CONTACT_POW_DIFFICULTY")
+                .map(|v| v.parse())
+                .unwrap_or(Ok(18))?,
+            max_difficulty: env::var("CONTACT_POW_MAX_DIFFICULTY")
+                .map(|v| v.parse())
+                .unwrap_or(Ok(24))?,
+            ttl: Duration::from_secs(
+                env::var("CONTACT_POW_TTL_SECS")
+                    .map(|v| v.parse())
+                    .unwrap_or(Ok(600))?,
+            ),
+            abuse_window: Duration::from_secs(
+                env::var("CONTACT_POW_ABUSE_WINDOW_SECS")
+                    .map(|v| v.parse())
+                    .unwrap_or(Ok(600))?,
+            ),
+            abuse_threshold: env::var("CONTACT_POW_ABUSE_THRESHOLD")
+                .map(|v| v.parse())
+                .unwrap_or(Ok(5))?,
+        };
+        if pow.max_difficulty < pow.base_difficulty {
+            anyhow::bail!("CONTACT_POW_MAX_DIFFICULTY must be at least CONTACT_POW_DIFFICULTY");
+        }
+        if pow.max_difficulty > MAX_DIFFICULTY {
+            anyhow::bail!(
+                "CONTACT_POW_MAX_DIFFICULTY must be at most {}",
+                MAX_DIFFICULTY
+            );
+        }
+
+        let captcha = captcha_config_from_env()?;
+
+        let duplicates = DuplicateConfig {
+            window: Duration::from_secs(
+                env::var("CONTACT_DUPLICATE_WINDOW_SECS")
+                    .map(|v| v.parse())
+                    .unwrap_or(Ok(3600))?,
+            ),
+            similarity_threshold: env::var("CONTACT_DUPLICATE_SIMILARITY")
+                .map(|v| v.parse())
+                .unwrap_or(Ok(0.8))?,
+            action: env::var("CONTACT_DUPLICATE_ACTION")
+                .unwrap_or_else(|_| "flag".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("CONTACT_DUPLICATE_ACTION: {}", e))?,
+        };
+        if !(0.0..=1.0).contains(&duplicates.similarity_threshold) {
+            anyhow::bail!("CONTACT_DUPLICATE_SIMILARITY must be between 0 and 1");
+        }
+
+        let email_blocklist_path = env::var("EMAIL_BLOCKLIST_PATH")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from);
+        let email_blocklist_reload_interval = Duration::from_secs(
+            env::var("EMAIL_BLOCKLIST_RELOAD_SECS")
+                .map(|v| v.parse())
+                .unwrap_or(Ok(30))?,
+        );
+        if email_blocklist_reload_interval.is_zero() {
+            anyhow::bail!("EMAIL_BLOCKLIST_RELOAD_SECS must be greater than 0");
+        }
+
+        let spam = SpamConfig {
+            quarantine_threshold: env::var("CONTACT_SPAM_THRESHOLD")
+                .map(|v| v.parse())
+                .unwrap_or(Ok(5.0))?,
+            max_links: env::var("CONTACT_SPAM_MAX_LINKS")
+                .map(|v| v.parse())
+                .unwrap_or(Ok(2))?,
+            blocked_words: list_from_env("CONTACT_SPAM_BLOCKED_WORDS"),
+            blocked_domains: list_from_env("CONTACT_SPAM_BLOCKED_DOMAINS"),
+            scripts: env::var("CONTACT_SPAM_SCRIPTS")
+                .unwrap_or_else(|_| "latin".to_string())
+                .split(',')
+                .map(|s| s.parse())
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| anyhow::anyhow!("CONTACT_SPAM_SCRIPTS: {}", e))?,
+            model_path: env::var("CONTACT_SPAM_MODEL_PATH")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(PathBuf::from),
+        };
+
+        // First matching rule wins, e.g. `/api/v1/contact=5/600,/api/v1/contact/*=30/60`
+        let rate_limits = env::var("RATE_LIMITS")
+            .unwrap_or_else(|_| DEFAULT_RATE_LIMITS.to_string())
+            .split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| s.parse().map_err(|e| anyhow::anyhow!("RATE_LIMITS: {}", e)))
+            .collect::<Result<Vec<_>>>()?;
+
+        // CIDRs or single addresses, forwarding headers from anyone else are ignored
+        let trusted_proxies = TrustedProxies::new(networks_from_env("TRUSTED_PROXIES")?)
+            .with_cloudflare(networks_from_env("CLOUDFLARE_PROXIES")?);
+
+        let idempotency_ttl = Duration::from_secs(
+            env::var("IDEMPOTENCY_TTL_SECS")
+                .map(|v| v.parse())
+                .unwrap_or(Ok(24 * 3600))?,
+        );
+
         Ok(Self {
             port,
             allowed_origins,
-            discord_bot_token,
-            discord_user_ids,
+            notifiers,
+            notifier_policy,
+            notification_templates_dir,
+            notification_locale,
+            outbox_path,
+            outbox,
             storage_config: Arc::new(StorageConfig {
                 r2_account_id,
                 r2_access_key_id,
                 r2_secret_access_key,
                 r2_bucket_name,
             }),
+            secondary_storage_config,
+            storage_health_check_interval_secs,
+            admin_api_key,
+            signing_secret,
+            bundle_manifest: Arc::new(bundle_manifest),
+            audit_log_path,
+            upload_key_prefix,
+            upload_allowed_content_types,
+            upload_max_size_bytes,
+            form_guard,
+            pow,
+            captcha,
+            duplicates,
+            email_blocklist_path,
+            email_blocklist_reload_interval,
+            spam,
+            rate_limits,
+            trusted_proxies,
+            idempotency_ttl,
         })
     }
 }
+
+fn notifier_config_from_env(name: &str) -> Result<NotifierConfig> {
+    Ok(match name {
+        "discord" => match env::var("DISCORD_MODE").as_deref().unwrap_or("dm") {
+            "dm" => NotifierConfig::Discord(discord_config_from_env()?),
+            "webhook" => NotifierConfig::DiscordWebhook(discord_webhook_config_from_env()?),
+            other => anyhow::bail!("DISCORD_MODE must be 'dm' or 'webhook', got '{}'", other),
+        },
+        "smtp" => NotifierConfig::Smtp(smtp_config_from_env()?),
+        "webhook" => NotifierConfig::Webhook(webhook_config_from_env()?),
+        other => anyhow::bail!(
+            "NOTIFIER entries must be 'discord', 'smtp' or 'webhook', got '{}'",
+            other
+        ),
+    })
+}
+
+fn discord_config_from_env() -> Result<DiscordConfig> {
+    let bot_token = env::var("DISCORD_BOT_TOKEN")
+        .map_err(|_| anyhow::anyhow!("DISCORD_BOT_TOKEN must be set"))?;
+
+    let user_ids = env::var("DISCORD_USER_IDS")
+        .map_err(|_| anyhow::anyhow!("DISCORD_USER_IDS must be set"))?
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    Ok(DiscordConfig {
+        bot_token,
+        user_ids,
+        api_base_url: env::var("DISCORD_API_BASE_URL")
+            .unwrap_or_else(|_| DISCORD_API_BASE_URL.to_string()),
+        dm_cache_path: env::var("DISCORD_DM_CACHE_PATH").ok().map(PathBuf::from),
+        max_concurrency: env::var("DISCORD_MAX_CONCURRENCY")
+            .map(|v| v.parse())
+            .unwrap_or(Ok(4))?,
+    })
+}
+
+fn discord_webhook_config_from_env() -> Result<DiscordWebhookConfig> {
+    let url = env::var("DISCORD_WEBHOOK_URL")
+        .map_err(|_| anyhow::anyhow!("DISCORD_WEBHOOK_URL must be set"))?;
+
+    Ok(DiscordWebhookConfig {
+        url,
+        username: env::var("DISCORD_WEBHOOK_USERNAME").ok(),
+        avatar_url: env::var("DISCORD_WEBHOOK_AVATAR_URL").ok(),
+        thread_id: env::var("DISCORD_WEBHOOK_THREAD_ID").ok(),
+        thread_name: env::var("DISCORD_WEBHOOK_THREAD_NAME").ok(),
+    })
+}
+
+fn smtp_config_from_env() -> Result<SmtpConfig> {
+    let host = env::var("SMTP_HOST").map_err(|_| anyhow::anyhow!("SMTP_HOST must be set"))?;
+
+    let tls = env::var("SMTP_TLS")
+        .unwrap_or_else(|_| "starttls".to_string())
+        .parse()
+        .map_err(|e| anyhow::anyhow!("SMTP_TLS: {}", e))?;
+
+    let default_port = match tls {
+        SmtpTls::None => 25,
+        SmtpTls::StartTls => 587,
+        SmtpTls::Implicit => 465,
+    };
+    let port = env::var("SMTP_PORT")
+        .map(|v| v.parse())
+        .unwrap_or(Ok(default_port))?;
+
+    let from = env::var("SMTP_FROM").map_err(|_| anyhow::anyhow!("SMTP_FROM must be set"))?;
+
+    let to = env::var("SMTP_TO")
+        .map_err(|_| anyhow::anyhow!("SMTP_TO must be set"))?
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let username = env::var("SMTP_USERNAME").ok();
+    let password = env::var("SMTP_PASSWORD").ok();
+    if username.is_some() != password.is_some() {
+        anyhow::bail!("SMTP_USERNAME and SMTP_PASSWORD must be set together");
+    }
+
+    Ok(SmtpConfig {
+        host,
+        port,
+        tls,
+        username,
+        password,
+        from,
+        to,
+    })
+}
+
+/// Reads `WEBHOOK_ENDPOINTS`, a comma separated list of `url` or `url|timeout_ms`.
+fn webhook_config_from_env() -> Result<WebhookConfig> {
+    let default_timeout_ms = env::var("WEBHOOK_TIMEOUT_MS")
+        .map(|v| v.parse())
+        .unwrap_or(Ok(5000))?;
+
+    let endpoints = env::var("WEBHOOK_ENDPOINTS")
+        .map_err(|_| anyhow::anyhow!("WEBHOOK_ENDPOINTS must be set"))?
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|entry| {
+            let (url, timeout_ms) = match entry.split_once('|') {
+                Some((url, timeout)) => (url, timeout.trim().parse()?),
+                None => (entry, default_timeout_ms),
+            };
+            Ok(WebhookEndpoint {
+                url: url.trim().to_string(),
+                timeout: Duration::from_millis(timeout_ms),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if endpoints.is_empty() {
+        anyhow::bail!("WEBHOOK_ENDPOINTS must contain at least one URL");
+    }
+
+    let secret =
+        env::var("WEBHOOK_SECRET").map_err(|_| anyhow::anyhow!("WEBHOOK_SECRET must be set"))?;
+
+    let max_retries = env::var("WEBHOOK_MAX_RETRIES")
+        .map(|v| v.parse())
+        .unwrap_or(Ok(3))?;
+
+    let retry_base_delay_ms = env::var("WEBHOOK_RETRY_BASE_DELAY_MS")
+        .map(|v| v.parse())
+        .unwrap_or(Ok(500))?;
+
+    Ok(WebhookConfig {
+        endpoints,
+        secret,
+        max_retries,
+        retry_base_delay: Duration::from_millis(retry_base_delay_ms),
+    })
+}
+
+/// Comma separated values, empty when unset.
+fn list_from_env(name: &str) -> Vec<String> {
+    env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn captcha_config_from_env() -> Result<Option<CaptchaConfig>> {
+    let provider = env::var("CAPTCHA_PROVIDER").unwrap_or_else(|_| "none".to_string());
+    if provider == "none" {
+        return Ok(None);
+    }
+
+    let secret =
+        env::var("CAPTCHA_SECRET").map_err(|_| anyhow::anyhow!("CAPTCHA_SECRET must be set"))?;
+    let verify_url = env::var("CAPTCHA_VERIFY_URL").ok();
+
+    Ok(Some(match provider.as_str() {
+        "turnstile" => CaptchaConfig::Turnstile {
+            secret,
+            verify_url: verify_url.unwrap_or_else(|| TURNSTILE_VERIFY_URL.to_string()),
+        },
+        "hcaptcha" => CaptchaConfig::HCaptcha {
+            secret,
+            verify_url: verify_url.unwrap_or_else(|| HCAPTCHA_VERIFY_URL.to_string()),
+            sitekey: env::var("CAPTCHA_SITEKEY").ok().filter(|s| !s.is_empty()),
+        },
+        other => anyhow::bail!(
+            "CAPTCHA_PROVIDER must be 'none', 'turnstile' or 'hcaptcha', got '{}'",
+            other
+        ),
+    }))
+}
+
+/// Comma separated CIDRs or single addresses from `name`, empty when unset.
+fn networks_from_env(name: &str) -> Result<Vec<IpNet>> {
+    list_from_env(name)
+        .iter()
+        .map(|s| {
+            s.parse::<IpNet>()
+                .or_else(|_| s.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|_| anyhow::anyhow!("{}: invalid network '{}'", name, s))
+        })
+        .collect()
+}
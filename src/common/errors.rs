@@ -1,6 +1,6 @@
 use axum::{
     Json,
-    http::StatusCode,
+    http::{HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
 };
 use serde_json::json;
@@ -13,9 +13,39 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Unprocessable entity: {0}")]
+    UnprocessableEntity(String),
+
+    #[error("Too many requests")]
+    RateLimited { retry_after_secs: u64 },
+
     #[error("Discord API error: {0}")]
     DiscordApi(String),
 
+    #[error("Email delivery error: {0}")]
+    Email(String),
+
+    #[error("Webhook delivery error: {0}")]
+    Webhook(String),
+
+    #[error("Notification not delivered: {0}")]
+    Notification(String),
+
+    #[error("Database error: {0}")]
+    Database(String),
+
+    #[error("CAPTCHA provider error: {0}")]
+    Captcha(String),
+
     #[error("HTTP client error: {0}")]
     HttpClient(#[from] reqwest::Error),
 
@@ -25,8 +55,21 @@ pub enum AppError {
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        let retry_after = match &self {
+            AppError::RateLimited { retry_after_secs } => Some(*retry_after_secs),
+            _ => None,
+        };
+
         let (status, error_message) = match self {
             AppError::Validation(msg) => (StatusCode::BAD_REQUEST, msg),
+            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized".to_string()),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+            AppError::UnprocessableEntity(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg),
+            AppError::RateLimited { .. } => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many requests".to_string(),
+            ),
             AppError::DiscordApi(msg) => {
                 tracing::error!("Discord API error: {}", msg);
                 (
@@ -34,6 +77,41 @@ impl IntoResponse for AppError {
                     "Failed to process request".to_string(),
                 )
             }
+            AppError::Email(msg) => {
+                tracing::error!("Email delivery error: {}", msg);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to process request".to_string(),
+                )
+            }
+            AppError::Webhook(msg) => {
+                tracing::error!("Webhook delivery error: {}", msg);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to process request".to_string(),
+                )
+            }
+            AppError::Notification(msg) => {
+                tracing::error!("Notification not delivered: {}", msg);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to process request".to_string(),
+                )
+            }
+            AppError::Database(msg) => {
+                tracing::error!("Database error: {}", msg);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to process request".to_string(),
+                )
+            }
+            AppError::Captcha(msg) => {
+                tracing::error!("CAPTCHA provider error: {}", msg);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to process request".to_string(),
+                )
+            }
             AppError::HttpClient(err) => {
                 tracing::error!("HTTP client error: {}", err);
                 (
@@ -41,11 +119,15 @@ impl IntoResponse for AppError {
                     "Failed to process request".to_string(),
                 )
             }
+            AppError::Storage(StorageError::NotFound(key)) => {
+                (StatusCode::NOT_FOUND, format!("Object not found: {}", key))
+            }
+            AppError::Storage(StorageError::InvalidKey(msg)) => (StatusCode::BAD_REQUEST, msg),
             AppError::Storage(err) => {
                 tracing::error!("Storage error: {}", err);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
-                    "Failed to generate presigned URL".to_string(),
+                    "Storage operation failed".to_string(),
                 )
             }
         };
@@ -54,7 +136,13 @@ impl IntoResponse for AppError {
             "error": error_message,
         }));
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let Some(secs) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
     }
 }
 
@@ -0,0 +1,477 @@
+use axum::{
+    Json,
+    body::{Body, Bytes, HttpBody},
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::common::{AppError, AppState};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Requests with a bigger body aren't buffered, matching the body limit layer.
+const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Responses with a bigger or unknown size are passed through without being
+/// stored, a retry runs the request again.
+const MAX_RESPONSE_BYTES: u64 = 1024 * 1024;
+
+/// Above this many stored responses, the expired ones are dropped, then the
+/// ones closest to expiring.
+const MAX_STORED_RESPONSES: usize = 10_000;
+
+/// Keys being processed are never evicted, new ones are refused past this.
+const MAX_IN_FLIGHT: usize = 1_000;
+
+/// A response as first sent, replayed to retries.
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl IntoResponse for StoredResponse {
+    fn into_response(self) -> Response {
+        let mut response = (self.status, self.body).into_response();
+        *response.headers_mut() = self.headers;
+        response
+            .headers_mut()
+            .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+        response
+    }
+}
+
+/// What to do with a request carrying an idempotency key.
+#[derive(Debug)]
+pub enum Attempt {
+    /// First time the key is seen, the request must be processed and then
+    /// [`IdempotencyStore::complete`]d or [`IdempotencyStore::abort`]ed.
+    Proceed,
+    Replay(StoredResponse),
+    /// The first request with this key hasn't been answered yet.
+    InFlight,
+    /// The key was used with another body.
+    Mismatch,
+    /// Too many keys are being processed to track another one.
+    Busy,
+}
+
+#[derive(Debug)]
+enum Slot {
+    InFlight,
+    Done(StoredResponse),
+}
+
+#[derive(Debug)]
+struct Entry {
+    body_hash: [u8; 32],
+    expires_at: Instant,
+    slot: Slot,
+}
+
+/// Key scoped to the route it was sent to. Not to the client IP, which can
+/// change between retries, e.g. on a mobile network.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IdempotencyScope {
+    pub route: String,
+    pub key: String,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    by_scope: HashMap<IdempotencyScope, Entry>,
+    /// Stored responses by expiry, in-flight keys aren't listed.
+    expiries: BTreeSet<(Instant, IdempotencyScope)>,
+}
+
+impl Entries {
+    fn in_flight(&self) -> usize {
+        self.by_scope.len() - self.expiries.len()
+    }
+
+    fn remove(&mut self, scope: &IdempotencyScope) {
+        if let Some(entry) = self.by_scope.remove(scope)
+            && matches!(entry.slot, Slot::Done(_))
+        {
+            self.expiries.remove(&(entry.expires_at, scope.clone()));
+        }
+    }
+
+    /// Drops the expired responses, then the ones closest to expiring until
+    /// there's room for another one.
+    fn evict(&mut self, now: Instant) {
+        while let Some((expires_at, scope)) = self.expiries.first().cloned() {
+            if expires_at > now && self.expiries.len() < MAX_STORED_RESPONSES {
+                break;
+            }
+            self.remove(&scope);
+        }
+    }
+}
+
+/// Responses to POST requests carrying an `Idempotency-Key`, kept for `ttl`
+/// so retries get the original response instead of running twice.
+pub struct IdempotencyStore {
+    ttl: Duration,
+    entries: Mutex<Entries>,
+}
+
+impl IdempotencyStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+
+    pub fn begin(&self, scope: &IdempotencyScope, body: &[u8], now: Instant) -> Attempt {
+        let body_hash: [u8; 32] = Sha256::digest(body).into();
+        let mut entries = self.entries.lock().expect("idempotency lock poisoned");
+
+        if let Some(entry) = entries
+            .by_scope
+            .get(scope)
+            .filter(|entry| entry.expires_at > now)
+        {
+            return if entry.body_hash != body_hash {
+                Attempt::Mismatch
+            } else {
+                match &entry.slot {
+                    Slot::InFlight => Attempt::InFlight,
+                    Slot::Done(response) => Attempt::Replay(response.clone()),
+                }
+            };
+        }
+
+        entries.evict(now);
+        entries.remove(scope);
+        if entries.in_flight() >= MAX_IN_FLIGHT {
+            return Attempt::Busy;
+        }
+        entries.by_scope.insert(
+            scope.clone(),
+            Entry {
+                body_hash,
+                expires_at: now + self.ttl,
+                slot: Slot::InFlight,
+            },
+        );
+        Attempt::Proceed
+    }
+
+    pub fn complete(&self, scope: &IdempotencyScope, response: StoredResponse) {
+        let mut entries = self.entries.lock().expect("idempotency lock poisoned");
+        let Some(entry) = entries.by_scope.get_mut(scope) else {
+            return;
+        };
+        let expires_at = entry.expires_at;
+        if matches!(
+            std::mem::replace(&mut entry.slot, Slot::Done(response)),
+            Slot::InFlight
+        ) {
+            entries.expiries.insert((expires_at, scope.clone()));
+        }
+    }
+
+    /// Forgets the key so the request can be retried, e.g. after a server error.
+    pub fn abort(&self, scope: &IdempotencyScope) {
+        self.entries
+            .lock()
+            .expect("idempotency lock poisoned")
+            .remove(scope);
+    }
+}
+
+/// Makes POST requests with an `Idempotency-Key` header safe to retry.
+///
+/// Server errors aren't stored, retrying them runs the request again.
+pub async fn idempotency_middleware(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let Some(key) = req.headers().get(IDEMPOTENCY_KEY_HEADER).cloned() else {
+        return Ok(next.run(req).await);
+    };
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string());
+    let Some(route) = route.filter(|_| req.method() == Method::POST) else {
+        return Ok(next.run(req).await);
+    };
+
+    let key = key
+        .to_str()
+        .ok()
+        .filter(|key| !key.is_empty() && key.len() <= 255)
+        .ok_or_else(|| {
+            AppError::Validation(
+                "Idempotency-Key: must be 1 to 255 visible ASCII characters".to_string(),
+            )
+        })?
+        .to_string();
+
+    let (parts, body) = req.into_parts();
+    let body = axum::body::to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|_| AppError::Validation("Request body too large".to_string()))?;
+
+    let scope = IdempotencyScope { route, key };
+    match state.idempotency.begin(&scope, &body, Instant::now()) {
+        Attempt::Proceed => {}
+        Attempt::Replay(response) => {
+            tracing::info!(route = %scope.route, "Replaying idempotent response");
+            state
+                .metrics
+                .incr("http_idempotent_replays_total", &[("route", &scope.route)]);
+            return Ok(response.into_response());
+        }
+        Attempt::InFlight => {
+            return Err(AppError::Conflict(
+                "A request with this Idempotency-Key is still being processed".to_string(),
+            ));
+        }
+        Attempt::Mismatch => {
+            return Err(AppError::UnprocessableEntity(
+                "Idempotency-Key was already used with a different request body".to_string(),
+            ));
+        }
+        Attempt::Busy => {
+            tracing::warn!(route = %scope.route, "Too many idempotent requests in flight");
+            return Ok((
+                StatusCode::SERVICE_UNAVAILABLE,
+                [(axum::http::header::RETRY_AFTER, "1")],
+                Json(json!({ "error": "Too many requests in progress, retry later" })),
+            )
+                .into_response());
+        }
+    }
+
+    // Released if the request is cancelled, e.g. when the client hangs up
+    let pending = Pending {
+        store: &state.idempotency,
+        scope: &scope,
+        completed: false,
+    };
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    if response.status().is_server_error() {
+        return Ok(response);
+    }
+    if response
+        .body()
+        .size_hint()
+        .upper()
+        .is_none_or(|size| size > MAX_RESPONSE_BYTES)
+    {
+        tracing::debug!(route = %scope.route, "Response too large to store for replay");
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_RESPONSE_BYTES as usize).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!("Failed to buffer idempotent response: {}", e);
+            return Ok((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Failed to process request" })),
+            )
+                .into_response());
+        }
+    };
+    pending.complete(StoredResponse {
+        status: parts.status,
+        headers: parts.headers.clone(),
+        body: body.clone(),
+    });
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+/// Key being processed, aborted when dropped before completion.
+struct Pending<'a> {
+    store: &'a IdempotencyStore,
+    scope: &'a IdempotencyScope,
+    completed: bool,
+}
+
+impl Pending<'_> {
+    fn complete(mut self, response: StoredResponse) {
+        self.store.complete(self.scope, response);
+        self.completed = true;
+    }
+}
+
+impl Drop for Pending<'_> {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.store.abort(self.scope);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(key: &str) -> IdempotencyScope {
+        IdempotencyScope {
+            route: "/api/v1/contact".to_string(),
+            key: key.to_string(),
+        }
+    }
+
+    fn stored() -> StoredResponse {
+        StoredResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(b"{\"success\":true}"),
+        }
+    }
+
+    #[test]
+    fn test_completed_request_is_replayed() {
+        let (store, now) = (
+            IdempotencyStore::new(Duration::from_secs(60)),
+            Instant::now(),
+        );
+
+        assert!(matches!(
+            store.begin(&scope("a"), b"body", now),
+            Attempt::Proceed
+        ));
+        assert!(matches!(
+            store.begin(&scope("a"), b"body", now),
+            Attempt::InFlight
+        ));
+
+        store.complete(&scope("a"), stored());
+        let Attempt::Replay(response) = store.begin(&scope("a"), b"body", now) else {
+            panic!("expected a replay");
+        };
+        assert_eq!(response.body, stored().body);
+        assert!(matches!(
+            store.begin(&scope("a"), b"other", now),
+            Attempt::Mismatch
+        ));
+    }
+
+    #[test]
+    fn test_keys_expire_and_can_be_aborted() {
+        let (store, now) = (
+            IdempotencyStore::new(Duration::from_secs(60)),
+            Instant::now(),
+        );
+        store.begin(&scope("a"), b"body", now);
+        store.complete(&scope("a"), stored());
+        store.begin(&scope("b"), b"body", now);
+        store.abort(&scope("b"));
+
+        let later = now + Duration::from_secs(61);
+        assert!(matches!(
+            store.begin(&scope("a"), b"other", later),
+            Attempt::Proceed
+        ));
+        assert!(matches!(
+            store.begin(&scope("b"), b"body", now),
+            Attempt::Proceed
+        ));
+    }
+
+    #[test]
+    fn test_keys_are_scoped_to_the_route() {
+        let (store, now) = (
+            IdempotencyStore::new(Duration::from_secs(60)),
+            Instant::now(),
+        );
+        store.begin(&scope("a"), b"body", now);
+
+        let other_route = IdempotencyScope {
+            route: "/api/v1/upload/form".to_string(),
+            ..scope("a")
+        };
+        assert!(matches!(
+            store.begin(&other_route, b"other", now),
+            Attempt::Proceed
+        ));
+    }
+
+    #[test]
+    fn test_entry_count_is_capped() {
+        let (store, now) = (
+            IdempotencyStore::new(Duration::from_secs(60)),
+            Instant::now(),
+        );
+        for i in 0..=MAX_STORED_RESPONSES {
+            let at = now + Duration::from_millis(i as u64);
+            store.begin(&scope(&i.to_string()), b"body", at);
+            store.complete(&scope(&i.to_string()), stored());
+        }
+
+        let entries = store.entries.lock().unwrap();
+        assert_eq!(entries.by_scope.len(), MAX_STORED_RESPONSES);
+        assert_eq!(entries.expiries.len(), MAX_STORED_RESPONSES);
+        assert!(!entries.by_scope.contains_key(&scope("0")));
+        assert!(
+            entries
+                .by_scope
+                .contains_key(&scope(&MAX_STORED_RESPONSES.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_in_flight_keys_are_never_evicted() {
+        let (store, now) = (
+            IdempotencyStore::new(Duration::from_secs(60)),
+            Instant::now(),
+        );
+        store.begin(&scope("slow"), b"body", now);
+        for i in 0..MAX_STORED_RESPONSES {
+            store.begin(&scope(&i.to_string()), b"body", now);
+            store.complete(&scope(&i.to_string()), stored());
+        }
+
+        assert!(matches!(
+            store.begin(&scope("slow"), b"body", now),
+            Attempt::InFlight
+        ));
+    }
+
+    #[test]
+    fn test_new_keys_are_refused_when_too_many_are_in_flight() {
+        let (store, now) = (
+            IdempotencyStore::new(Duration::from_secs(60)),
+            Instant::now(),
+        );
+        for i in 0..MAX_IN_FLIGHT {
+            store.begin(&scope(&i.to_string()), b"body", now);
+        }
+
+        assert!(matches!(
+            store.begin(&scope("new"), b"body", now),
+            Attempt::Busy
+        ));
+        assert!(matches!(
+            store.begin(&scope("0"), b"body", now),
+            Attempt::InFlight
+        ));
+
+        store.complete(&scope("0"), stored());
+        assert!(matches!(
+            store.begin(&scope("new"), b"body", now),
+            Attempt::Proceed
+        ));
+    }
+}
@@ -0,0 +1,508 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::task::JoinHandle;
+
+use super::{
+    BackendStatus, ObjectMetadata, ObjectStream, PresignedPut, StorageClient, StorageError,
+    StorageStatus, UploadPolicy,
+};
+
+// Consecutive backend failures before traffic is routed away from a backend
+const FAILURE_THRESHOLD: u32 = 2;
+
+#[derive(Default)]
+struct Health {
+    consecutive_failures: u32,
+    last_error: Option<String>,
+    last_checked: Option<DateTime<Utc>>,
+}
+
+struct Backend {
+    name: &'static str,
+    client: Arc<dyn StorageClient>,
+    health: Mutex<Health>,
+}
+
+impl Backend {
+    fn new(name: &'static str, client: Arc<dyn StorageClient>) -> Self {
+        Self {
+            name,
+            client,
+            health: Mutex::new(Health::default()),
+        }
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.health
+            .lock()
+            .expect("backend health lock poisoned")
+            .consecutive_failures
+            < FAILURE_THRESHOLD
+    }
+
+    fn record_success(&self) {
+        let mut health = self.health.lock().expect("backend health lock poisoned");
+        health.consecutive_failures = 0;
+        health.last_error = None;
+        health.last_checked = Some(Utc::now());
+    }
+
+    fn record_failure(&self, error: &StorageError) {
+        let mut health = self.health.lock().expect("backend health lock poisoned");
+        health.consecutive_failures += 1;
+        health.last_error = Some(error.to_string());
+        health.last_checked = Some(Utc::now());
+    }
+
+    fn status(&self) -> BackendStatus {
+        let health = self.health.lock().expect("backend health lock poisoned");
+        BackendStatus {
+            name: self.name.to_string(),
+            healthy: health.consecutive_failures < FAILURE_THRESHOLD,
+            consecutive_failures: health.consecutive_failures,
+            last_error: health.last_error.clone(),
+            last_checked: health.last_checked.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// Whether an operation talks to the backend. Presigning is computed locally,
+/// so its success says nothing about the backend being reachable.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Reach {
+    Local,
+    Remote,
+}
+
+/// Storage composite routing requests to a primary backend and falling back to
+/// a replicated secondary one when the primary is unhealthy.
+///
+/// Reads and presigning fail over; writes always target the primary, the
+/// secondary being a replica of it.
+pub struct FailoverStorage {
+    primary: Backend,
+    secondary: Backend,
+}
+
+impl FailoverStorage {
+    pub fn new(primary: Arc<dyn StorageClient>, secondary: Arc<dyn StorageClient>) -> Self {
+        Self {
+            primary: Backend::new("primary", primary),
+            secondary: Backend::new("secondary", secondary),
+        }
+    }
+
+    /// Backends in the order they should be tried: healthy ones first, the
+    /// primary before the secondary.
+    fn ordered(&self) -> [&Backend; 2] {
+        if !self.primary.is_healthy() && self.secondary.is_healthy() {
+            [&self.secondary, &self.primary]
+        } else {
+            [&self.primary, &self.secondary]
+        }
+    }
+
+    fn active_backend(&self) -> &'static str {
+        self.ordered()[0].name
+    }
+
+    async fn with_failover<T, F, Fut>(&self, reach: Reach, operation: F) -> Result<T, StorageError>
+    where
+        F: Fn(Arc<dyn StorageClient>) -> Fut,
+        Fut: Future<Output = Result<T, StorageError>>,
+    {
+        let mut last_error = None;
+
+        for backend in self.ordered() {
+            match operation(backend.client.clone()).await {
+                Err(e) if e.is_backend_failure() => {
+                    tracing::warn!(backend = backend.name, "Storage backend failed: {}", e);
+                    backend.record_failure(&e);
+                    last_error = Some(e);
+                }
+                result => {
+                    if reach == Reach::Remote {
+                        backend.record_success();
+                    }
+                    return result;
+                }
+            }
+        }
+
+        Err(last_error.expect("failover always tries at least one backend"))
+    }
+
+    async fn on_primary<T, Fut>(&self, operation: Fut) -> Result<T, StorageError>
+    where
+        Fut: Future<Output = Result<T, StorageError>>,
+    {
+        let result = operation.await;
+        match &result {
+            Err(e) if e.is_backend_failure() => self.primary.record_failure(e),
+            _ => self.primary.record_success(),
+        }
+        result
+    }
+
+    /// Probes both backends once and updates their health.
+    pub async fn probe(&self) {
+        let before = self.active_backend();
+
+        for backend in [&self.primary, &self.secondary] {
+            match backend.client.check_health().await {
+                Ok(()) => backend.record_success(),
+                Err(e) => {
+                    tracing::warn!(backend = backend.name, "Storage health check failed: {}", e);
+                    backend.record_failure(&e);
+                }
+            }
+        }
+
+        let after = self.active_backend();
+        if before != after {
+            tracing::warn!(
+                from = before,
+                to = after,
+                "Storage failover: active backend changed"
+            );
+        }
+    }
+
+    /// Probes the backends every `interval` for the lifetime of the process.
+    pub fn spawn_health_monitor(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.probe().await;
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl StorageClient for FailoverStorage {
+    async fn generate_presigned_get_url(
+        &self,
+        object_key: &str,
+        expires_in_secs: u64,
+    ) -> Result<String, StorageError> {
+        self.with_failover(Reach::Local, |client| async move {
+            client
+                .generate_presigned_get_url(object_key, expires_in_secs)
+                .await
+        })
+        .await
+    }
+
+    async fn generate_presigned_put(
+        &self,
+        object_key: &str,
+        policy: &UploadPolicy,
+    ) -> Result<PresignedPut, StorageError> {
+        // Uploads must land where later writes and reads expect them
+        self.on_primary(
+            self.primary
+                .client
+                .generate_presigned_put(object_key, policy),
+        )
+        .await
+    }
+
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        self.with_failover(Reach::Remote, |client| async move {
+            client.list_objects(prefix).await
+        })
+        .await
+    }
+
+    async fn get_object(&self, object_key: &str) -> Result<ObjectStream, StorageError> {
+        self.with_failover(Reach::Remote, |client| async move {
+            client.get_object(object_key).await
+        })
+        .await
+    }
+
+    async fn head_object(&self, object_key: &str) -> Result<ObjectMetadata, StorageError> {
+        self.with_failover(Reach::Remote, |client| async move {
+            client.head_object(object_key).await
+        })
+        .await
+    }
+
+    async fn delete_object(&self, object_key: &str) -> Result<(), StorageError> {
+        self.on_primary(self.primary.client.delete_object(object_key))
+            .await
+    }
+
+    async fn copy_object(
+        &self,
+        source_key: &str,
+        destination_key: &str,
+    ) -> Result<(), StorageError> {
+        self.on_primary(self.primary.client.copy_object(source_key, destination_key))
+            .await
+    }
+
+    async fn move_object(
+        &self,
+        source_key: &str,
+        destination_key: &str,
+    ) -> Result<(), StorageError> {
+        self.on_primary(self.primary.client.move_object(source_key, destination_key))
+            .await
+    }
+
+    async fn check_health(&self) -> Result<(), StorageError> {
+        self.probe().await;
+        if self.primary.is_healthy() || self.secondary.is_healthy() {
+            Ok(())
+        } else {
+            Err(StorageError::S3Error(
+                "no healthy storage backend".to_string(),
+            ))
+        }
+    }
+
+    fn status(&self) -> StorageStatus {
+        StorageStatus {
+            active_backend: self.active_backend().to_string(),
+            backends: vec![self.primary.status(), self.secondary.status()],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        collections::BTreeMap,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    struct FakeBackend {
+        name: &'static str,
+        down: AtomicBool,
+    }
+
+    impl FakeBackend {
+        fn new(name: &'static str) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                down: AtomicBool::new(false),
+            })
+        }
+
+        fn set_down(&self, down: bool) {
+            self.down.store(down, Ordering::SeqCst);
+        }
+
+        fn check(&self) -> Result<(), StorageError> {
+            if self.down.load(Ordering::SeqCst) {
+                Err(StorageError::S3Error(format!("{} unreachable", self.name)))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[async_trait]
+    impl StorageClient for FakeBackend {
+        async fn generate_presigned_get_url(
+            &self,
+            object_key: &str,
+            _expires_in_secs: u64,
+        ) -> Result<String, StorageError> {
+            Ok(format!("https://{}/{}", self.name, object_key))
+        }
+
+        async fn generate_presigned_put(
+            &self,
+            object_key: &str,
+            _policy: &UploadPolicy,
+        ) -> Result<PresignedPut, StorageError> {
+            self.check()?;
+            Ok(PresignedPut {
+                url: format!("https://{}/{}", self.name, object_key),
+                headers: BTreeMap::new(),
+                expires_at: String::new(),
+            })
+        }
+
+        async fn list_objects(&self, _prefix: &str) -> Result<Vec<String>, StorageError> {
+            self.check()?;
+            Ok(vec![self.name.to_string()])
+        }
+
+        async fn get_object(&self, _object_key: &str) -> Result<ObjectStream, StorageError> {
+            self.check()?;
+            let chunk = Ok(bytes::Bytes::from_static(self.name.as_bytes()));
+            Ok(Box::pin(futures_util::stream::iter([chunk])))
+        }
+
+        async fn head_object(&self, object_key: &str) -> Result<ObjectMetadata, StorageError> {
+            self.check()?;
+            if object_key == "missing" {
+                return Err(StorageError::NotFound(object_key.to_string()));
+            }
+            Ok(ObjectMetadata {
+                etag: Some(self.name.to_string()),
+                ..ObjectMetadata::default()
+            })
+        }
+
+        async fn delete_object(&self, _object_key: &str) -> Result<(), StorageError> {
+            self.check()
+        }
+
+        async fn copy_object(
+            &self,
+            _source_key: &str,
+            _destination_key: &str,
+        ) -> Result<(), StorageError> {
+            self.check()
+        }
+
+        async fn check_health(&self) -> Result<(), StorageError> {
+            self.check()
+        }
+    }
+
+    fn setup() -> (Arc<FakeBackend>, Arc<FakeBackend>, FailoverStorage) {
+        let primary = FakeBackend::new("primary");
+        let secondary = FakeBackend::new("secondary");
+        let storage = FailoverStorage::new(primary.clone(), secondary.clone());
+        (primary, secondary, storage)
+    }
+
+    #[tokio::test]
+    async fn test_presign_uses_primary_when_healthy() {
+        let (_, _, storage) = setup();
+        storage.probe().await;
+
+        let url = storage
+            .generate_presigned_get_url("a.mp4", 60)
+            .await
+            .unwrap();
+        assert_eq!(url, "https://primary/a.mp4");
+        assert_eq!(storage.status().active_backend, "primary");
+    }
+
+    #[tokio::test]
+    async fn test_presign_falls_back_after_failed_probes() {
+        let (primary, _, storage) = setup();
+        primary.set_down(true);
+
+        storage.probe().await;
+        assert_eq!(storage.status().active_backend, "primary");
+
+        storage.probe().await;
+        assert_eq!(storage.status().active_backend, "secondary");
+
+        let url = storage
+            .generate_presigned_get_url("a.mp4", 60)
+            .await
+            .unwrap();
+        assert_eq!(url, "https://secondary/a.mp4");
+
+        let status = storage.status();
+        assert!(!status.backends[0].healthy);
+        assert!(status.backends[0].last_error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_presign_does_not_mask_unhealthy_primary() {
+        let (primary, _, storage) = setup();
+        primary.set_down(true);
+        storage.probe().await;
+
+        storage
+            .generate_presigned_get_url("a.mp4", 60)
+            .await
+            .unwrap();
+        storage.probe().await;
+
+        assert_eq!(storage.status().active_backend, "secondary");
+    }
+
+    #[tokio::test]
+    async fn test_primary_recovers() {
+        let (primary, _, storage) = setup();
+        primary.set_down(true);
+        storage.probe().await;
+        storage.probe().await;
+        assert_eq!(storage.status().active_backend, "secondary");
+
+        primary.set_down(false);
+        storage.probe().await;
+        assert_eq!(storage.status().active_backend, "primary");
+    }
+
+    #[tokio::test]
+    async fn test_reads_retry_on_secondary() {
+        let (primary, _, storage) = setup();
+        primary.set_down(true);
+
+        let metadata = storage.head_object("a.mp4").await.unwrap();
+        assert_eq!(metadata.etag.as_deref(), Some("secondary"));
+    }
+
+    #[tokio::test]
+    async fn test_not_found_is_not_a_backend_failure() {
+        let (_, _, storage) = setup();
+
+        let result = storage.head_object("missing").await;
+        assert!(matches!(result, Err(StorageError::NotFound(_))));
+        assert_eq!(storage.status().backends[0].consecutive_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_writes_stay_on_primary() {
+        let (primary, _, storage) = setup();
+        primary.set_down(true);
+
+        assert!(storage.delete_object("a.mp4").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_upload_urls_stay_on_primary() {
+        let (primary, _, storage) = setup();
+        let policy = UploadPolicy {
+            content_type: "video/mp4".to_string(),
+            content_length: 10,
+            expires_in_secs: 60,
+        };
+
+        let upload = storage
+            .generate_presigned_put("uploads/a.mp4", &policy)
+            .await
+            .unwrap();
+        assert_eq!(upload.url, "https://primary/uploads/a.mp4");
+
+        primary.set_down(true);
+        storage.probe().await;
+        storage.probe().await;
+        assert_eq!(storage.status().active_backend, "secondary");
+        assert!(
+            storage
+                .generate_presigned_put("uploads/a.mp4", &policy)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_healthy_backend() {
+        let (primary, secondary, storage) = setup();
+        primary.set_down(true);
+        secondary.set_down(true);
+
+        assert!(storage.list_objects("").await.is_err());
+        assert!(storage.check_health().await.is_err());
+    }
+}
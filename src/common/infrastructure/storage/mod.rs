@@ -0,0 +1,156 @@
+mod failover;
+mod r2;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::Stream;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+};
+
+pub use failover::FailoverStorage;
+pub use r2::{R2Storage, create_r2_client, create_s3_client};
+
+pub struct StorageConfig {
+    pub r2_account_id: String,
+    pub r2_access_key_id: String,
+    pub r2_secret_access_key: String,
+    pub r2_bucket_name: String,
+}
+
+/// Connection settings for a generic S3-compatible backend.
+pub struct S3CompatibleConfig {
+    pub endpoint_url: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub bucket_name: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("S3 operation failed: {0}")]
+    S3Error(String),
+
+    #[error("Invalid object key: {0}")]
+    InvalidKey(String),
+
+    #[error("Object not found: {0}")]
+    NotFound(String),
+
+    #[error("Presigned URL generation failed: {0}")]
+    PresignError(String),
+}
+
+impl StorageError {
+    /// Whether the error points at the backend itself rather than the request,
+    /// i.e. whether retrying on another backend could succeed.
+    pub fn is_backend_failure(&self) -> bool {
+        matches!(
+            self,
+            StorageError::S3Error(_) | StorageError::PresignError(_)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ObjectMetadata {
+    pub size: u64,
+    pub content_type: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendStatus {
+    pub name: String,
+    pub healthy: bool,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub last_checked: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageStatus {
+    pub active_backend: String,
+    pub backends: Vec<BackendStatus>,
+}
+
+/// Request a browser upload URL is signed for, the bucket rejects uploads
+/// sending other values.
+#[derive(Debug, Clone)]
+pub struct UploadPolicy {
+    pub content_type: String,
+    pub content_length: u64,
+    pub expires_in_secs: u64,
+}
+
+/// URL the browser `PUT`s the file to, along with headers it must send as is.
+#[derive(Debug, Clone, Serialize)]
+pub struct PresignedPut {
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    pub expires_at: String,
+}
+
+pub type ObjectStream = Pin<Box<dyn Stream<Item = Result<Bytes, StorageError>> + Send>>;
+
+#[async_trait]
+pub trait StorageClient: Send + Sync {
+    async fn generate_presigned_get_url(
+        &self,
+        object_key: &str,
+        expires_in_secs: u64,
+    ) -> Result<String, StorageError>;
+
+    /// Generates a presigned PUT URL for a direct browser upload of `object_key`.
+    ///
+    /// Presigned PUTs rather than POST policies, which R2 doesn't support.
+    async fn generate_presigned_put(
+        &self,
+        object_key: &str,
+        policy: &UploadPolicy,
+    ) -> Result<PresignedPut, StorageError>;
+
+    /// Lists every object key under `prefix`, following pagination.
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<String>, StorageError>;
+
+    /// Streams the object body chunk by chunk without buffering it.
+    async fn get_object(&self, object_key: &str) -> Result<ObjectStream, StorageError>;
+
+    async fn head_object(&self, object_key: &str) -> Result<ObjectMetadata, StorageError>;
+
+    async fn delete_object(&self, object_key: &str) -> Result<(), StorageError>;
+
+    async fn copy_object(
+        &self,
+        source_key: &str,
+        destination_key: &str,
+    ) -> Result<(), StorageError>;
+
+    /// Moves an object by copying it then deleting the source.
+    ///
+    /// This is not atomic: if the delete fails both copies are left in place.
+    async fn move_object(
+        &self,
+        source_key: &str,
+        destination_key: &str,
+    ) -> Result<(), StorageError> {
+        self.copy_object(source_key, destination_key).await?;
+        self.delete_object(source_key).await
+    }
+
+    /// Probes the backend, e.g. with a bucket HEAD request.
+    async fn check_health(&self) -> Result<(), StorageError>;
+
+    /// Reports which backend currently serves requests.
+    fn status(&self) -> StorageStatus {
+        StorageStatus {
+            active_backend: "primary".to_string(),
+            backends: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use async_trait::async_trait;
+use aws_sdk_s3::{
+    Client as S3Client,
+    config::{Credentials, Region},
+    presigning::PresigningConfig,
+    primitives::{ByteStream, DateTimeFormat},
+};
+use futures_util::stream;
+use std::time::Duration;
+
+use super::{
+    ObjectMetadata, ObjectStream, PresignedPut, S3CompatibleConfig, StorageClient, StorageConfig,
+    StorageError, UploadPolicy,
+};
+
+pub struct R2Storage {
+    client: S3Client,
+    bucket_name: String,
+}
+
+impl R2Storage {
+    pub fn new(config: &StorageConfig) -> Self {
+        Self::from_s3_compatible(&S3CompatibleConfig {
+            endpoint_url: r2_endpoint(&config.r2_account_id),
+            region: "auto".to_string(),
+            access_key_id: config.r2_access_key_id.clone(),
+            secret_access_key: config.r2_secret_access_key.clone(),
+            bucket_name: config.r2_bucket_name.clone(),
+        })
+    }
+
+    /// Builds a client for any S3-compatible provider, e.g. a replica bucket.
+    pub fn from_s3_compatible(config: &S3CompatibleConfig) -> Self {
+        let client = create_s3_client(
+            &config.endpoint_url,
+            &config.region,
+            &config.access_key_id,
+            &config.secret_access_key,
+        );
+
+        Self {
+            client,
+            bucket_name: config.bucket_name.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl StorageClient for R2Storage {
+    async fn generate_presigned_get_url(
+        &self,
+        object_key: &str,
+        expires_in_secs: u64,
+    ) -> Result<String, StorageError> {
+        validate_object_key(object_key)?;
+
+        let presigning_config = PresigningConfig::builder()
+            .expires_in(Duration::from_secs(expires_in_secs))
+            .build()
+            .map_err(|e| StorageError::PresignError(e.to_string()))?;
+
+        let presigned_request = self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(object_key)
+            .presigned(presigning_config)
+            .await
+            .map_err(|e| StorageError::S3Error(e.to_string()))?;
+
+        Ok(presigned_request.uri().to_string())
+    }
+
+    async fn generate_presigned_put(
+        &self,
+        object_key: &str,
+        policy: &UploadPolicy,
+    ) -> Result<PresignedPut, StorageError> {
+        validate_object_key(object_key)?;
+
+        let expires_in = Duration::from_secs(policy.expires_in_secs);
+        let presigning_config = PresigningConfig::builder()
+            .expires_in(expires_in)
+            .build()
+            .map_err(|e| StorageError::PresignError(e.to_string()))?;
+
+        // Both headers are signed, so the upload must match the declared file
+        let presigned_request = self
+            .client
+            .put_object()
+            .bucket(&self.bucket_name)
+            .key(object_key)
+            .content_type(&policy.content_type)
+            .content_length(policy.content_length as i64)
+            .presigned(presigning_config)
+            .await
+            .map_err(|e| StorageError::S3Error(e.to_string()))?;
+
+        Ok(PresignedPut {
+            url: presigned_request.uri().to_string(),
+            headers: presigned_request
+                .headers()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            expires_at: (chrono::Utc::now() + expires_in).to_rfc3339(),
+        })
+    }
+
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        let mut keys = Vec::new();
+        let mut continuation_token = None;
+
+        loop {
+            let output = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket_name)
+                .prefix(prefix)
+                .set_continuation_token(continuation_token)
+                .send()
+                .await
+                .map_err(|e| StorageError::S3Error(e.to_string()))?;
+
+            keys.extend(
+                output
+                    .contents()
+                    .iter()
+                    .filter_map(|object| object.key().map(str::to_string)),
+            );
+
+            match output.next_continuation_token() {
+                Some(token) if output.is_truncated().unwrap_or(false) => {
+                    continuation_token = Some(token.to_string());
+                }
+                _ => break,
+            }
+        }
+
+        Ok(keys)
+    }
+
+    async fn get_object(&self, object_key: &str) -> Result<ObjectStream, StorageError> {
+        validate_object_key(object_key)?;
+
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(object_key)
+            .send()
+            .await
+            .map_err(|e| match e.into_service_error() {
+                e if e.is_no_such_key() => StorageError::NotFound(object_key.to_string()),
+                e => StorageError::S3Error(e.to_string()),
+            })?;
+
+        Ok(byte_stream(output.body))
+    }
+
+    async fn head_object(&self, object_key: &str) -> Result<ObjectMetadata, StorageError> {
+        validate_object_key(object_key)?;
+
+        let output = self
+            .client
+            .head_object()
+            .bucket(&self.bucket_name)
+            .key(object_key)
+            .send()
+            .await
+            .map_err(|e| match e.into_service_error() {
+                e if e.is_not_found() => StorageError::NotFound(object_key.to_string()),
+                e => StorageError::S3Error(e.to_string()),
+            })?;
+
+        Ok(ObjectMetadata {
+            size: output.content_length().unwrap_or(0).max(0) as u64,
+            content_type: output.content_type().map(str::to_string),
+            etag: output.e_tag().map(str::to_string),
+            last_modified: output
+                .last_modified()
+                .and_then(|date| date.fmt(DateTimeFormat::DateTime).ok()),
+            metadata: output.metadata().cloned().unwrap_or_default(),
+        })
+    }
+
+    async fn delete_object(&self, object_key: &str) -> Result<(), StorageError> {
+        validate_object_key(object_key)?;
+
+        self.client
+            .delete_object()
+            .bucket(&self.bucket_name)
+            .key(object_key)
+            .send()
+            .await
+            .map_err(|e| StorageError::S3Error(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn copy_object(
+        &self,
+        source_key: &str,
+        destination_key: &str,
+    ) -> Result<(), StorageError> {
+        validate_object_key(source_key)?;
+        validate_object_key(destination_key)?;
+
+        self.client
+            .copy_object()
+            .bucket(&self.bucket_name)
+            .copy_source(format!(
+                "{}/{}",
+                self.bucket_name,
+                encode_copy_source(source_key)
+            ))
+            .key(destination_key)
+            .send()
+            .await
+            .map_err(|e| StorageError::S3Error(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn check_health(&self) -> Result<(), StorageError> {
+        self.client
+            .head_bucket()
+            .bucket(&self.bucket_name)
+            .send()
+            .await
+            .map_err(|e| StorageError::S3Error(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+fn byte_stream(body: ByteStream) -> ObjectStream {
+    Box::pin(stream::try_unfold(body, |mut body| async move {
+        match body.try_next().await {
+            Ok(Some(chunk)) => Ok(Some((chunk, body))),
+            Ok(None) => Ok(None),
+            Err(e) => Err(StorageError::S3Error(e.to_string())),
+        }
+    }))
+}
+
+/// Percent-encodes a key for the `x-amz-copy-source` header, keeping `/` separators.
+fn encode_copy_source(key: &str) -> String {
+    key.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn validate_object_key(object_key: &str) -> Result<(), StorageError> {
+    if object_key.is_empty() {
+        return Err(StorageError::InvalidKey(
+            "Object key must not be empty".to_string(),
+        ));
+    }
+
+    if object_key.starts_with('/') {
+        return Err(StorageError::InvalidKey(
+            "Object key must not start with /".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn create_r2_client(
+    account_id: &str,
+    access_key_id: &str,
+    secret_access_key: &str,
+) -> S3Client {
+    create_s3_client(
+        &r2_endpoint(account_id),
+        "auto",
+        access_key_id,
+        secret_access_key,
+    )
+}
+
+fn r2_endpoint(account_id: &str) -> String {
+    format!("https://{}.r2.cloudflarestorage.com", account_id)
+}
+
+pub fn create_s3_client(
+    endpoint: &str,
+    region: &str,
+    access_key_id: &str,
+    secret_access_key: &str,
+) -> S3Client {
+    let credentials = Credentials::new(access_key_id, secret_access_key, None, None, "r2_creds");
+
+    let config = aws_sdk_s3::config::Config::builder()
+        .credentials_provider(credentials)
+        .endpoint_url(endpoint)
+        .region(Region::new(region.to_string()))
+        .force_path_style(true)
+        .behavior_version(aws_sdk_s3::config::BehaviorVersion::latest())
+        .build();
+
+    S3Client::from_conf(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_copy_source() {
+        assert_eq!(
+            encode_copy_source("videos/my reel (final).mp4"),
+            "videos/my%20reel%20%28final%29.mp4"
+        );
+        assert_eq!(encode_copy_source("é.mp4"), "%C3%A9.mp4");
+    }
+
+    #[tokio::test]
+    async fn test_presigned_put_signs_type_and_length() {
+        let storage = R2Storage::from_s3_compatible(&S3CompatibleConfig {
+            endpoint_url: "https://account.r2.cloudflarestorage.com".to_string(),
+            region: "auto".to_string(),
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "secret".to_string(),
+            bucket_name: "bucket".to_string(),
+        });
+        let policy = UploadPolicy {
+            content_type: "application/pdf".to_string(),
+            content_length: 2048,
+            expires_in_secs: 600,
+        };
+
+        let upload = storage
+            .generate_presigned_put("uploads/42/brief.pdf", &policy)
+            .await
+            .unwrap();
+
+        assert!(upload.url.contains("uploads/42/brief.pdf"));
+        assert!(upload.url.contains("X-Amz-Expires=600"));
+        let signed_headers = upload
+            .url
+            .split('&')
+            .find_map(|param| param.strip_prefix("X-Amz-SignedHeaders="))
+            .unwrap();
+        assert!(signed_headers.contains("content-length"));
+        assert!(signed_headers.contains("content-type"));
+        assert_eq!(upload.headers["content-type"], "application/pdf");
+        assert_eq!(upload.headers["content-length"], "2048");
+    }
+}
@@ -0,0 +1,123 @@
+use std::{collections::BTreeMap, fmt::Write, sync::Mutex};
+
+/// In-process counters and gauges, rendered in the Prometheus text format.
+///
+/// Series are keyed by name and labels, e.g.
+/// `discord_rate_limited_total{scope="user"}`.
+#[derive(Default)]
+pub struct Metrics {
+    counters: Mutex<BTreeMap<String, u64>>,
+    gauges: Mutex<BTreeMap<String, f64>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn incr(&self, name: &str, labels: &[(&str, &str)]) {
+        *self
+            .counters
+            .lock()
+            .expect("metrics lock poisoned")
+            .entry(series(name, labels))
+            .or_default() += 1;
+    }
+
+    pub fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.gauges
+            .lock()
+            .expect("metrics lock poisoned")
+            .insert(series(name, labels), value);
+    }
+
+    pub fn counter(&self, name: &str, labels: &[(&str, &str)]) -> u64 {
+        self.counters
+            .lock()
+            .expect("metrics lock poisoned")
+            .get(&series(name, labels))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn gauge(&self, name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        self.gauges
+            .lock()
+            .expect("metrics lock poisoned")
+            .get(&series(name, labels))
+            .copied()
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        render_family(
+            &mut output,
+            "counter",
+            self.counters
+                .lock()
+                .expect("metrics lock poisoned")
+                .iter()
+                .map(|(k, v)| (k.clone(), v.to_string())),
+        );
+        render_family(
+            &mut output,
+            "gauge",
+            self.gauges
+                .lock()
+                .expect("metrics lock poisoned")
+                .iter()
+                .map(|(k, v)| (k.clone(), v.to_string())),
+        );
+        output
+    }
+}
+
+fn series(name: &str, labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return name.to_string();
+    }
+    let labels = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, v.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{}{{{}}}", name, labels)
+}
+
+/// Writes one `# TYPE` line per metric name followed by its series, which
+/// arrive sorted so all series of a name are contiguous.
+fn render_family(output: &mut String, kind: &str, series: impl Iterator<Item = (String, String)>) {
+    let mut current = String::new();
+    for (key, value) in series {
+        let name = key.split('{').next().unwrap_or(&key);
+        if name != current {
+            current = name.to_string();
+            let _ = writeln!(output, "# TYPE {} {}", current, kind);
+        }
+        let _ = writeln!(output, "{} {}", key, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let metrics = Metrics::new();
+        metrics.incr("requests_total", &[("status", "200")]);
+        metrics.incr("requests_total", &[("status", "200")]);
+        metrics.incr("requests_total", &[("status", "429")]);
+        metrics.set_gauge("remaining", &[("bucket", "abc")], 4.0);
+
+        assert_eq!(
+            metrics.render(),
+            "# TYPE requests_total counter\n\
+             requests_total{status=\"200\"} 2\n\
+             requests_total{status=\"429\"} 1\n\
+             # TYPE remaining gauge\n\
+             remaining{bucket=\"abc\"} 4\n"
+        );
+        assert_eq!(metrics.counter("requests_total", &[("status", "200")]), 2);
+    }
+}
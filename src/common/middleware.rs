@@ -1,20 +1,219 @@
-use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use axum::{
+    extract::{ConnectInfo, FromRequestParts, MatchedPath, Request, State},
+    http::{HeaderMap, HeaderValue, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use ipnet::IpNet;
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 use uuid::Uuid;
 
+use crate::common::{AppError, AppState};
+
 pub const REQUEST_ID_HEADER: &str = "x-request-id";
 
+/// Request identifier stored in the request extensions by [`request_id_middleware`].
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Address of the client, as resolved by [`client_ip_middleware`] through
+/// the trusted proxies, or of the peer that opened the connection when the
+/// middleware isn't installed. `None` when the server isn't started with
+/// `into_make_service_with_connect_info`.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(client_ip) = parts.extensions.get::<ClientIp>() {
+            return Ok(*client_ip);
+        }
+        Ok(ClientIp(
+            parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip()),
+        ))
+    }
+}
+
+/// Networks of the proxies in front of the app (Cloudflare, the reverse
+/// proxy), the only peers whose forwarding headers are believed.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    proxies: Vec<IpNet>,
+    /// Cloudflare edge ranges, trusted like `proxies` and the only hops
+    /// allowed to set `CF-Connecting-IP`.
+    cloudflare: Vec<IpNet>,
+}
+
+impl TrustedProxies {
+    pub fn new(networks: Vec<IpNet>) -> Self {
+        Self {
+            proxies: networks,
+            cloudflare: Vec::new(),
+        }
+    }
+
+    pub fn with_cloudflare(mut self, networks: Vec<IpNet>) -> Self {
+        self.cloudflare = networks;
+        self
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.proxies
+            .iter()
+            .chain(&self.cloudflare)
+            .any(|network| network.contains(&ip))
+    }
+
+    fn is_cloudflare(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.cloudflare.iter().any(|network| network.contains(&ip))
+    }
+
+    /// Client address of a request received from `peer`.
+    ///
+    /// Headers are only read when `peer` is trusted, in this order:
+    /// `CF-Connecting-IP`, `X-Forwarded-For` then `Forwarded`. Forwarding
+    /// chains are walked from the closest hop, the first address outside the
+    /// trusted networks being the client's. `CF-Connecting-IP` is only
+    /// believed when that walk reaches a Cloudflare hop first, anyone
+    /// talking to the local proxy directly could set it otherwise.
+    pub fn resolve(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        if !self.contains(peer) {
+            return peer;
+        }
+
+        let chain = forwarding_chain(headers, "x-forwarded-for", |entry| Some(entry))
+            .or_else(|| forwarding_chain(headers, "forwarded", forwarded_for));
+
+        if let Some(ip) = headers
+            .get("cf-connecting-ip")
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_node)
+            && self.delivered_by_cloudflare(peer, chain.as_deref().unwrap_or_default())
+        {
+            return ip;
+        }
+
+        let Some(chain) = chain else {
+            return peer;
+        };
+
+        let mut client = peer;
+        for hop in chain.into_iter().rev() {
+            // Garbage can't be told apart from a spoofed value, stop at the
+            // last proxy that could be vouched for
+            let Some(ip) = hop else { break };
+            client = ip;
+            if !self.contains(ip) {
+                break;
+            }
+        }
+        client
+    }
+
+    /// Whether the request entered the trusted proxies through Cloudflare.
+    fn delivered_by_cloudflare(&self, peer: IpAddr, chain: &[Option<IpAddr>]) -> bool {
+        if self.is_cloudflare(peer) {
+            return true;
+        }
+        for hop in chain.iter().rev() {
+            match hop {
+                Some(ip) if self.is_cloudflare(*ip) => return true,
+                Some(ip) if self.contains(*ip) => continue,
+                _ => return false,
+            }
+        }
+        false
+    }
+}
+
+/// Hops listed by every `name` header, `None` when there's no such header.
+fn forwarding_chain(
+    headers: &HeaderMap,
+    name: &str,
+    node: impl Fn(&str) -> Option<&str>,
+) -> Option<Vec<Option<IpAddr>>> {
+    let chain = headers
+        .get_all(name)
+        .iter()
+        .flat_map(|value| value.to_str().unwrap_or_default().split(','))
+        .map(|entry| node(entry.trim()).and_then(parse_node))
+        .collect::<Vec<_>>();
+    (!chain.is_empty()).then_some(chain)
+}
+
+/// Value of the `for` parameter of a `Forwarded` element.
+fn forwarded_for(element: &str) -> Option<&str> {
+    element.split(';').find_map(|pair| {
+        let (key, value) = pair.trim().split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("for")
+            .then(|| value.trim().trim_matches('"'))
+    })
+}
+
+/// Parses `192.0.2.1`, `192.0.2.1:4242`, `2001:db8::1` or `[2001:db8::1]:4242`.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim();
+    if let Some(rest) = node.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse().ok();
+    }
+    node.parse()
+        .ok()
+        .or_else(|| node.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+        .map(|ip: IpAddr| ip.to_canonical())
+}
+
+/// Resolves the client address once per request, see [`TrustedProxies::resolve`],
+/// for [`ClientIp`] to pick up.
+pub async fn client_ip_middleware(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let client_ip = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| {
+            state
+                .config
+                .trusted_proxies
+                .resolve(addr.ip().to_canonical(), req.headers())
+        });
+    req.extensions_mut().insert(ClientIp(client_ip));
+    next.run(req).await
+}
+
 #[tracing::instrument(skip(req, next))]
 pub async fn request_id_middleware(mut req: Request, next: Next) -> Response {
     let request_id = Uuid::new_v4().to_string();
+    let client_ip = req
+        .extensions()
+        .get::<ClientIp>()
+        .and_then(|ClientIp(ip)| *ip);
 
     tracing::info!(
         request_id = %request_id,
+        client_ip = client_ip.map(tracing::field::display),
         method = %req.method(),
         uri = %req.uri(),
         "Incoming request"
     );
 
-    req.extensions_mut().insert(request_id.clone());
+    req.extensions_mut().insert(RequestId(request_id.clone()));
 
     let response = next.run(req).await;
 
@@ -33,3 +232,338 @@ pub async fn request_id_middleware(mut req: Request, next: Next) -> Response {
 
     response
 }
+
+/// Above this many tracked clients, the ones whose allowance is full again
+/// are forgotten.
+const MAX_TRACKED_CLIENTS: usize = 100_000;
+
+/// `limit` requests per `period` on the routes matching `route`, either an
+/// exact route path like `/api/v1/contact` or a prefix ending with `*`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitRule {
+    pub route: String,
+    pub limit: u32,
+    pub period: Duration,
+}
+
+impl RateLimitRule {
+    fn matches(&self, path: &str) -> bool {
+        match self.route.strip_suffix('*') {
+            Some(prefix) => path.starts_with(prefix),
+            None => path == self.route,
+        }
+    }
+}
+
+/// Parses `<route>=<limit>/<period in seconds>`, e.g. `/api/v1/contact=5/600`.
+impl FromStr for RateLimitRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{}' must look like '<route>=<limit>/<seconds>'", s);
+        let (route, policy) = s.trim().split_once('=').ok_or_else(invalid)?;
+        let (limit, period) = policy.split_once('/').ok_or_else(invalid)?;
+        let limit: u32 = limit.trim().parse().map_err(|_| invalid())?;
+        let period: u64 = period.trim().parse().map_err(|_| invalid())?;
+        if !route.starts_with('/') || limit == 0 || period == 0 {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            route: route.trim().to_string(),
+            limit,
+            period: Duration::from_secs(period),
+        })
+    }
+}
+
+/// Outcome of a rate limit check, rendered as the `RateLimit-*` headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub period: Duration,
+    pub remaining: u32,
+    /// Until the allowance is full again.
+    pub reset: Duration,
+    /// Until the next request is allowed, zero when this one was.
+    pub retry_after: Duration,
+}
+
+impl RateLimitDecision {
+    fn apply(&self, headers: &mut HeaderMap) {
+        headers.insert("ratelimit-limit", HeaderValue::from(self.limit));
+        headers.insert("ratelimit-remaining", HeaderValue::from(self.remaining));
+        headers.insert("ratelimit-reset", HeaderValue::from(ceil_secs(self.reset)));
+        if let Ok(policy) =
+            HeaderValue::from_str(&format!("{};w={}", self.limit, self.period.as_secs()))
+        {
+            headers.insert("ratelimit-policy", policy);
+        }
+    }
+}
+
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+/// Per-client rate limiting with the generic cell rate algorithm, a token
+/// bucket that only stores when the bucket will be full again.
+///
+/// The first rule matching a route wins, so list exact routes before the
+/// prefixes covering them.
+pub struct RateLimiter {
+    rules: Vec<RateLimitRule>,
+    /// Theoretical arrival time of the next request per (rule, client).
+    arrivals: Mutex<HashMap<(usize, IpAddr), Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(rules: Vec<RateLimitRule>) -> Self {
+        Self {
+            rules,
+            arrivals: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn rules(&self) -> &[RateLimitRule] {
+        &self.rules
+    }
+
+    /// Counts a request from `ip` on `route`, `None` when no rule covers it.
+    pub fn check(&self, route: &str, ip: IpAddr, now: Instant) -> Option<RateLimitDecision> {
+        let (index, rule) = self
+            .rules
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| rule.matches(route))?;
+        let interval = rule.period / rule.limit;
+        let horizon = now + rule.period;
+
+        let mut arrivals = self.arrivals.lock().expect("rate limiter lock poisoned");
+        let arrival = arrivals.get(&(index, ip)).copied().unwrap_or(now).max(now);
+        let next_arrival = arrival + interval;
+
+        let decision = if next_arrival > horizon {
+            RateLimitDecision {
+                allowed: false,
+                limit: rule.limit,
+                period: rule.period,
+                remaining: 0,
+                reset: arrival - now,
+                retry_after: next_arrival - horizon,
+            }
+        } else {
+            arrivals.insert((index, ip), next_arrival);
+            RateLimitDecision {
+                allowed: true,
+                limit: rule.limit,
+                period: rule.period,
+                remaining: ((horizon - next_arrival).as_nanos() / interval.as_nanos()) as u32,
+                reset: next_arrival - now,
+                retry_after: Duration::ZERO,
+            }
+        };
+
+        if arrivals.len() > MAX_TRACKED_CLIENTS {
+            arrivals.retain(|_, arrival| *arrival > now);
+        }
+        Some(decision)
+    }
+}
+
+/// Applies [`AppState::rate_limiter`] to the matched route, keyed by client IP.
+///
+/// Requests without a known client IP aren't limited.
+pub async fn rate_limit_middleware(
+    State(state): State<AppState>,
+    ClientIp(client_ip): ClientIp,
+    req: Request,
+    next: Next,
+) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string());
+    let decision = match (route.as_deref(), client_ip) {
+        (Some(route), Some(ip)) => state
+            .rate_limiter
+            .check(route, ip, Instant::now())
+            .map(|decision| (route, decision)),
+        _ => None,
+    };
+    let Some((route, decision)) = decision else {
+        return next.run(req).await;
+    };
+
+    let mut response = if decision.allowed {
+        next.run(req).await
+    } else {
+        tracing::warn!(
+            route,
+            client_ip = ?client_ip,
+            retry_after_ms = decision.retry_after.as_millis() as u64,
+            "Rate limit exceeded"
+        );
+        state
+            .metrics
+            .incr("http_rate_limited_total", &[("route", route)]);
+        AppError::RateLimited {
+            retry_after_secs: ceil_secs(decision.retry_after).max(1),
+        }
+        .into_response()
+    };
+    decision.apply(response.headers_mut());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IP: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(203, 0, 113, 7));
+
+    fn proxies() -> TrustedProxies {
+        TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap()])
+            .with_cloudflare(vec!["173.245.48.0/20".parse().unwrap()])
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    const PROXY: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(10, 0, 0, 2));
+
+    #[test]
+    fn test_headers_from_untrusted_peers_are_ignored() {
+        let spoofed = headers(&[
+            ("cf-connecting-ip", "198.51.100.1"),
+            ("x-forwarded-for", "198.51.100.1"),
+        ]);
+
+        assert_eq!(proxies().resolve(IP, &spoofed), IP);
+        assert_eq!(TrustedProxies::default().resolve(PROXY, &spoofed), PROXY);
+    }
+
+    #[test]
+    fn test_cloudflare_header_wins() {
+        let headers = headers(&[
+            ("cf-connecting-ip", "203.0.113.7"),
+            ("x-forwarded-for", "198.51.100.1, 173.245.48.1"),
+        ]);
+
+        assert_eq!(proxies().resolve(PROXY, &headers), IP);
+        assert_eq!(
+            proxies().resolve(IpAddr::from([173, 245, 48, 1]), &headers),
+            IP
+        );
+    }
+
+    #[test]
+    fn test_cloudflare_header_is_ignored_when_cloudflare_was_bypassed() {
+        // Sent straight to the local proxy, which appended the real peer
+        let spoofed = headers(&[
+            ("cf-connecting-ip", "198.51.100.1"),
+            ("x-forwarded-for", "173.245.48.1, 203.0.113.7"),
+        ]);
+        assert_eq!(proxies().resolve(PROXY, &spoofed), IP);
+
+        let without_chain = headers(&[("cf-connecting-ip", "198.51.100.1")]);
+        assert_eq!(proxies().resolve(PROXY, &without_chain), PROXY);
+    }
+
+    #[test]
+    fn test_forwarded_for_chain_stops_at_first_untrusted_hop() {
+        // The client forged the first entry, then went through Cloudflare
+        let headers = headers(&[
+            ("x-forwarded-for", "198.51.100.1, 203.0.113.7"),
+            ("x-forwarded-for", "173.245.48.1"),
+        ]);
+        assert_eq!(proxies().resolve(PROXY, &headers), IP);
+
+        let all_trusted = self::headers(&[("x-forwarded-for", "10.1.1.1, 173.245.48.1")]);
+        assert_eq!(
+            proxies().resolve(PROXY, &all_trusted),
+            IpAddr::from([10, 1, 1, 1])
+        );
+
+        let garbage = self::headers(&[("x-forwarded-for", "203.0.113.7, bogus, 10.1.1.1")]);
+        assert_eq!(
+            proxies().resolve(PROXY, &garbage),
+            IpAddr::from([10, 1, 1, 1])
+        );
+    }
+
+    #[test]
+    fn test_standard_forwarded_header() {
+        let headers = headers(&[(
+            "forwarded",
+            r#"for="[2001:db8:cafe::17]:4711";proto=https, for=10.1.1.1;by=10.0.0.2"#,
+        )]);
+
+        assert_eq!(
+            proxies().resolve(PROXY, &headers),
+            "2001:db8:cafe::17".parse::<IpAddr>().unwrap()
+        );
+    }
+
+    fn limiter() -> RateLimiter {
+        RateLimiter::new(vec![
+            "/api/v1/contact=3/60".parse().unwrap(),
+            "/api/v1/video*=100/1".parse().unwrap(),
+        ])
+    }
+
+    #[test]
+    fn test_rule_parsing() {
+        assert_eq!(
+            "/api/v1/contact=5/600".parse(),
+            Ok(RateLimitRule {
+                route: "/api/v1/contact".to_string(),
+                limit: 5,
+                period: Duration::from_secs(600),
+            })
+        );
+        assert!("/api/v1/contact=5".parse::<RateLimitRule>().is_err());
+        assert!("contact=5/600".parse::<RateLimitRule>().is_err());
+        assert!("/api/v1/contact=0/600".parse::<RateLimitRule>().is_err());
+    }
+
+    #[test]
+    fn test_burst_then_steady_rate() {
+        let (limiter, now) = (limiter(), Instant::now());
+
+        let remaining = (0..3)
+            .map(|_| limiter.check("/api/v1/contact", IP, now).unwrap())
+            .map(|decision| decision.remaining)
+            .collect::<Vec<_>>();
+        assert_eq!(remaining, vec![2, 1, 0]);
+
+        let rejected = limiter.check("/api/v1/contact", IP, now).unwrap();
+        assert!(!rejected.allowed);
+        assert_eq!(rejected.retry_after, Duration::from_secs(20));
+        assert_eq!(rejected.reset, Duration::from_secs(60));
+
+        // One request is allowed again every 20 seconds
+        let later = now + Duration::from_secs(20);
+        assert!(limiter.check("/api/v1/contact", IP, later).unwrap().allowed);
+        assert!(!limiter.check("/api/v1/contact", IP, later).unwrap().allowed);
+    }
+
+    #[test]
+    fn test_clients_and_routes_are_independent() {
+        let (limiter, now) = (limiter(), Instant::now());
+        for _ in 0..3 {
+            limiter.check("/api/v1/contact", IP, now);
+        }
+
+        let other_ip = limiter.check("/api/v1/contact", [198, 51, 100, 1].into(), now);
+        assert!(other_ip.unwrap().allowed);
+        assert!(limiter.check("/api/v1/video", IP, now).unwrap().allowed);
+        assert_eq!(limiter.check("/api/v1/health", IP, now), None);
+    }
+}
@@ -1,7 +1,12 @@
+pub mod audit;
+pub mod auth;
 pub mod config;
 pub mod errors;
+pub mod idempotency;
 pub mod infrastructure;
+pub mod metrics;
 pub mod middleware;
+pub mod signing;
 pub mod state;
 
 pub use config::AppConfig;
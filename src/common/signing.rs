@@ -0,0 +1,161 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum SigningError {
+    #[error("malformed token")]
+    Malformed,
+
+    #[error("invalid signature")]
+    InvalidSignature,
+
+    #[error("token intended for another purpose")]
+    WrongAudience,
+
+    #[error("token expired")]
+    Expired,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    aud: String,
+    exp: i64,
+    claims: T,
+}
+
+/// Issues and verifies compact `payload.signature` tokens signed with HMAC-SHA256.
+///
+/// Every token carries an audience so that a token minted for one feature
+/// cannot be replayed against another one sharing the same secret.
+pub struct Signer {
+    key: Vec<u8>,
+}
+
+impl Signer {
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            key: secret.as_ref().to_vec(),
+        }
+    }
+
+    /// Creates a signer with a random key, tokens won't survive a restart.
+    pub fn ephemeral() -> Self {
+        Self::new(rand::random::<[u8; 32]>())
+    }
+
+    pub fn sign<T: Serialize>(&self, audience: &str, claims: &T, expires_at: i64) -> String {
+        let envelope = Envelope {
+            aud: audience.to_string(),
+            exp: expires_at,
+            claims,
+        };
+        let payload = URL_SAFE_NO_PAD
+            .encode(serde_json::to_vec(&envelope).expect("claims must serialize to JSON"));
+        let signature = URL_SAFE_NO_PAD.encode(self.mac(payload.as_bytes()));
+        format!("{}.{}", payload, signature)
+    }
+
+    pub fn verify<T: DeserializeOwned>(
+        &self,
+        audience: &str,
+        token: &str,
+    ) -> Result<T, SigningError> {
+        self.verify_at(audience, token, chrono::Utc::now().timestamp())
+    }
+
+    pub fn verify_at<T: DeserializeOwned>(
+        &self,
+        audience: &str,
+        token: &str,
+        now: i64,
+    ) -> Result<T, SigningError> {
+        let (payload, signature) = token.split_once('.').ok_or(SigningError::Malformed)?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| SigningError::Malformed)?;
+
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key size");
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| SigningError::InvalidSignature)?;
+
+        let payload = URL_SAFE_NO_PAD
+            .decode(payload)
+            .map_err(|_| SigningError::Malformed)?;
+        let envelope: Envelope<T> =
+            serde_json::from_slice(&payload).map_err(|_| SigningError::Malformed)?;
+
+        if envelope.aud != audience {
+            return Err(SigningError::WrongAudience);
+        }
+        if envelope.exp < now {
+            return Err(SigningError::Expired);
+        }
+
+        Ok(envelope.claims)
+    }
+
+    /// Raw HMAC-SHA256 of `data` with the signer key.
+    pub fn mac(&self, data: &[u8]) -> Vec<u8> {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key size");
+        mac.update(data);
+        mac.finalize().into_bytes().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Claims {
+        name: String,
+    }
+
+    fn claims() -> Claims {
+        Claims {
+            name: "press-kit".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let signer = Signer::new("secret");
+        let token = signer.sign("bundle", &claims(), 200);
+        assert_eq!(signer.verify_at("bundle", &token, 100), Ok(claims()));
+    }
+
+    #[test]
+    fn test_expired_token() {
+        let signer = Signer::new("secret");
+        let token = signer.sign("bundle", &claims(), 100);
+        assert_eq!(
+            signer.verify_at::<Claims>("bundle", &token, 101),
+            Err(SigningError::Expired)
+        );
+    }
+
+    #[test]
+    fn test_wrong_audience() {
+        let signer = Signer::new("secret");
+        let token = signer.sign("bundle", &claims(), 200);
+        assert_eq!(
+            signer.verify_at::<Claims>("contact", &token, 100),
+            Err(SigningError::WrongAudience)
+        );
+    }
+
+    #[test]
+    fn test_foreign_signature() {
+        let signer = Signer::new("secret");
+        let token = Signer::new("other").sign("bundle", &claims(), 200);
+        assert_eq!(
+            signer.verify_at::<Claims>("bundle", &token, 100),
+            Err(SigningError::InvalidSignature)
+        );
+    }
+}
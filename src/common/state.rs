@@ -1,9 +1,22 @@
 use std::sync::Arc;
 use std::time::SystemTime;
 
+use crate::common::audit::{AuditLog, JsonlAuditLog};
 use crate::common::config::AppConfig;
-use crate::common::infrastructure::storage::{R2Storage, StorageClient};
-use crate::domains::contact::service::{DiscordNotifier, Notification};
+use crate::common::idempotency::IdempotencyStore;
+use crate::common::infrastructure::storage::{FailoverStorage, R2Storage, StorageClient};
+use crate::common::metrics::Metrics;
+use crate::common::middleware::{RateLimiter, TrustedProxies};
+use crate::common::signing::Signer;
+use crate::domains::bundle::BundleManifest;
+use crate::domains::contact::captcha::build_captcha;
+use crate::domains::contact::service::{
+    Notification, NotificationTemplates, NotifierConfig, build_notifier,
+};
+use crate::domains::contact::{
+    CaptchaVerifier, DuplicateDetector, EmailPolicy, FormGuardConfig, Outbox, ProofOfWork,
+    SpamFilter,
+};
 
 #[derive(Clone)]
 pub struct AppState {
@@ -13,50 +26,150 @@ pub struct AppState {
     pub start_time: SystemTime,
     pub storage: Arc<dyn StorageClient>,
     pub notifier: Arc<dyn Notification>,
+    pub templates: Arc<NotificationTemplates>,
+    pub outbox: Arc<Outbox>,
+    pub pow: Arc<ProofOfWork>,
+    pub duplicates: Arc<DuplicateDetector>,
+    pub email_policy: Arc<EmailPolicy>,
+    pub spam: Arc<SpamFilter>,
+    /// Checked on contact submissions when a provider is configured.
+    pub captcha: Option<Arc<dyn CaptchaVerifier>>,
+    pub signer: Arc<Signer>,
+    pub bundles: Arc<BundleManifest>,
+    pub audit: Arc<dyn AuditLog>,
+    pub metrics: Arc<Metrics>,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub idempotency: Arc<IdempotencyStore>,
 }
 
 pub struct PublicConfig {
     pub discord_user_ids: Vec<String>,
     pub r2_account_id: String,
+    pub upload_key_prefix: String,
+    pub upload_allowed_content_types: Vec<String>,
+    pub upload_max_size_bytes: u64,
+    pub form_guard: FormGuardConfig,
+    pub trusted_proxies: TrustedProxies,
 }
 
 pub struct Secrets {
-    pub discord_bot_token: String,
+    pub discord_bot_token: Option<String>,
+    pub smtp_password: Option<String>,
     pub r2_access_key_id: String,
     pub r2_secret_access_key: String,
+    pub admin_api_key: Option<String>,
 }
 
 impl AppState {
-    pub fn new(config: AppConfig) -> Self {
+    pub fn new(config: AppConfig) -> anyhow::Result<Self> {
         let http_client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(10))
             .build()
             .expect("Failed to create HTTP client");
 
-        // Initialize storage service
-        let storage = Arc::new(R2Storage::new(&config.storage_config));
+        // Initialize storage service, with failover when a replica is configured
+        let primary = Arc::new(R2Storage::new(&config.storage_config));
+        let storage: Arc<dyn StorageClient> = match &config.secondary_storage_config {
+            Some(secondary) => {
+                let failover = Arc::new(FailoverStorage::new(
+                    primary,
+                    Arc::new(R2Storage::from_s3_compatible(secondary)),
+                ));
+                failover
+                    .clone()
+                    .spawn_health_monitor(std::time::Duration::from_secs(
+                        config.storage_health_check_interval_secs,
+                    ));
+                failover
+            }
+            None => primary,
+        };
 
         // Initialize notifier service
-        let notifier = Arc::new(DiscordNotifier::new(
+        let metrics = Arc::new(Metrics::new());
+
+        let templates = Arc::new(NotificationTemplates::load(
+            config.notification_templates_dir.as_deref(),
+            &config.notification_locale,
+        )?);
+
+        let notifier = build_notifier(
+            &config.notifiers,
+            config.notifier_policy,
             http_client.clone(),
-            config.discord_bot_token.clone(),
-            config.discord_user_ids.clone(),
-        ));
+            metrics.clone(),
+            templates.clone(),
+        )?;
+
+        let outbox = Arc::new(Outbox::open(&config.outbox_path, config.outbox)?);
+        outbox.clone().spawn_worker(notifier.clone());
+
+        let mut discord_user_ids = Vec::new();
+        let mut discord_bot_token = None;
+        let mut smtp_password = None;
+        for notifier in config.notifiers {
+            match notifier {
+                NotifierConfig::Discord(discord) => {
+                    discord_user_ids = discord.user_ids;
+                    discord_bot_token = Some(discord.bot_token);
+                }
+                NotifierConfig::Smtp(smtp) => smtp_password = smtp.password,
+                NotifierConfig::DiscordWebhook(_) | NotifierConfig::Webhook(_) => {}
+            }
+        }
 
-        Self {
+        let email_policy = Arc::new(EmailPolicy::new(config.email_blocklist_path.clone())?);
+        email_policy
+            .clone()
+            .spawn_reloader(config.email_blocklist_reload_interval);
+
+        let captcha = config
+            .captcha
+            .as_ref()
+            .map(|captcha| build_captcha(captcha, http_client.clone()));
+
+        let signer = match &config.signing_secret {
+            Some(secret) => Signer::new(secret),
+            None => {
+                tracing::warn!("SIGNING_SECRET is not set, signed links won't survive a restart");
+                Signer::ephemeral()
+            }
+        };
+
+        Ok(Self {
             config: Arc::new(PublicConfig {
-                discord_user_ids: config.discord_user_ids,
+                discord_user_ids,
                 r2_account_id: config.storage_config.r2_account_id.clone(),
+                upload_key_prefix: config.upload_key_prefix,
+                upload_allowed_content_types: config.upload_allowed_content_types,
+                upload_max_size_bytes: config.upload_max_size_bytes,
+                form_guard: config.form_guard,
+                trusted_proxies: config.trusted_proxies,
             }),
             secrets: Arc::new(Secrets {
-                discord_bot_token: config.discord_bot_token,
+                discord_bot_token,
+                smtp_password,
                 r2_access_key_id: config.storage_config.r2_access_key_id.clone(),
                 r2_secret_access_key: config.storage_config.r2_secret_access_key.clone(),
+                admin_api_key: config.admin_api_key,
             }),
             http_client,
             start_time: SystemTime::now(),
             storage,
             notifier,
-        }
+            templates,
+            outbox,
+            pow: Arc::new(ProofOfWork::new(config.pow)),
+            duplicates: Arc::new(DuplicateDetector::new(config.duplicates)),
+            email_policy,
+            spam: Arc::new(SpamFilter::new(config.spam)?),
+            captcha,
+            signer: Arc::new(signer),
+            bundles: config.bundle_manifest,
+            audit: Arc::new(JsonlAuditLog::new(config.audit_log_path)),
+            metrics,
+            rate_limiter: Arc::new(RateLimiter::new(config.rate_limits)),
+            idempotency: Arc::new(IdempotencyStore::new(config.idempotency_ttl)),
+        })
     }
 }
@@ -0,0 +1,317 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use futures_util::{Stream, StreamExt, stream};
+use std::{io, sync::Arc};
+use tokio::sync::mpsc;
+
+use crate::common::infrastructure::storage::StorageClient;
+
+const LOCAL_FILE_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const DATA_DESCRIPTOR_SIGNATURE: u32 = 0x0807_4b50;
+const CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+
+const VERSION: u16 = 20;
+// Bit 3: sizes and CRC are written in a data descriptor after the data,
+// bit 11: entry names are UTF-8.
+const FLAGS: u16 = 0x0808;
+const METHOD_STORE: u16 = 0;
+
+// Number of chunks buffered ahead of the client before backpressure kicks in
+const CHANNEL_CAPACITY: usize = 8;
+
+/// Streams a ZIP archive of `keys`, fetching each object from storage as the
+/// client consumes the response.
+///
+/// Entries are stored without compression (media files are already
+/// compressed) and their CRC and sizes are written in data descriptors, so
+/// nothing is buffered beyond the chunk in flight. ZIP64 is not supported:
+/// the stream fails if the archive would exceed 4 GiB.
+pub fn stream_zip(
+    storage: Arc<dyn StorageClient>,
+    keys: Vec<String>,
+) -> impl Stream<Item = Result<Bytes, io::Error>> + Send + 'static {
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        if let Err(e) = write_archive(storage.as_ref(), &keys, &tx).await {
+            tracing::error!("Failed to stream bundle archive: {}", e);
+            let _ = tx.send(Err(e)).await;
+        }
+    });
+
+    stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|item| (item, rx))
+    })
+}
+
+async fn write_archive(
+    storage: &dyn StorageClient,
+    keys: &[String],
+    tx: &mpsc::Sender<Result<Bytes, io::Error>>,
+) -> Result<(), io::Error> {
+    let mut writer = ZipWriter::new(Utc::now());
+
+    for key in keys {
+        let mut body = storage.get_object(key).await.map_err(io::Error::other)?;
+
+        send(tx, writer.start_entry(key)?).await?;
+
+        let mut crc = crc32fast::Hasher::new();
+        let mut size: u64 = 0;
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk.map_err(io::Error::other)?;
+            crc.update(&chunk);
+            size += chunk.len() as u64;
+            send(tx, chunk).await?;
+        }
+
+        send(tx, writer.finish_entry(crc.finalize(), size)?).await?;
+        tracing::debug!(object_key = %key, size, "Added object to bundle");
+    }
+
+    send(tx, writer.finish()?).await
+}
+
+async fn send(tx: &mpsc::Sender<Result<Bytes, io::Error>>, chunk: Bytes) -> Result<(), io::Error> {
+    tx.send(Ok(chunk))
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "client went away"))
+}
+
+struct CentralEntry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Produces the byte segments surrounding each entry's data.
+struct ZipWriter {
+    dos_time: u16,
+    dos_date: u16,
+    offset: u64,
+    current: Option<(String, u64)>,
+    entries: Vec<CentralEntry>,
+}
+
+impl ZipWriter {
+    fn new(now: DateTime<Utc>) -> Self {
+        let dos_time =
+            ((now.hour() as u16) << 11) | ((now.minute() as u16) << 5) | (now.second() as u16 / 2);
+        let dos_date = (((now.year() - 1980).max(0) as u16) << 9)
+            | ((now.month() as u16) << 5)
+            | now.day() as u16;
+
+        Self {
+            dos_time,
+            dos_date,
+            offset: 0,
+            current: None,
+            entries: Vec::new(),
+        }
+    }
+
+    fn start_entry(&mut self, name: &str) -> Result<Bytes, io::Error> {
+        let name_len = u16::try_from(name.len())
+            .map_err(|_| io::Error::other(format!("entry name too long: {}", name)))?;
+
+        let mut buf = BytesMut::with_capacity(30 + name.len());
+        buf.put_u32_le(LOCAL_FILE_HEADER_SIGNATURE);
+        buf.put_u16_le(VERSION);
+        buf.put_u16_le(FLAGS);
+        buf.put_u16_le(METHOD_STORE);
+        buf.put_u16_le(self.dos_time);
+        buf.put_u16_le(self.dos_date);
+        buf.put_u32_le(0); // crc, see data descriptor
+        buf.put_u32_le(0); // compressed size
+        buf.put_u32_le(0); // uncompressed size
+        buf.put_u16_le(name_len);
+        buf.put_u16_le(0); // extra field length
+        buf.put_slice(name.as_bytes());
+
+        self.current = Some((name.to_string(), self.offset));
+        self.offset += buf.len() as u64;
+        Ok(buf.freeze())
+    }
+
+    fn finish_entry(&mut self, crc: u32, size: u64) -> Result<Bytes, io::Error> {
+        let (name, offset) = self
+            .current
+            .take()
+            .ok_or_else(|| io::Error::other("no entry in progress"))?;
+        let size = u32::try_from(size).map_err(|_| too_large())?;
+
+        let mut buf = BytesMut::with_capacity(16);
+        buf.put_u32_le(DATA_DESCRIPTOR_SIGNATURE);
+        buf.put_u32_le(crc);
+        buf.put_u32_le(size); // compressed size
+        buf.put_u32_le(size); // uncompressed size
+
+        self.entries.push(CentralEntry {
+            name,
+            crc,
+            size,
+            offset: u32::try_from(offset).map_err(|_| too_large())?,
+        });
+        self.offset += size as u64 + buf.len() as u64;
+        Ok(buf.freeze())
+    }
+
+    fn finish(self) -> Result<Bytes, io::Error> {
+        let entry_count = u16::try_from(self.entries.len())
+            .map_err(|_| io::Error::other("too many entries for a ZIP archive"))?;
+        let directory_offset = u32::try_from(self.offset).map_err(|_| too_large())?;
+
+        let mut buf = BytesMut::new();
+        for entry in &self.entries {
+            buf.put_u32_le(CENTRAL_DIRECTORY_SIGNATURE);
+            buf.put_u16_le(VERSION); // version made by
+            buf.put_u16_le(VERSION); // version needed to extract
+            buf.put_u16_le(FLAGS);
+            buf.put_u16_le(METHOD_STORE);
+            buf.put_u16_le(self.dos_time);
+            buf.put_u16_le(self.dos_date);
+            buf.put_u32_le(entry.crc);
+            buf.put_u32_le(entry.size);
+            buf.put_u32_le(entry.size);
+            buf.put_u16_le(entry.name.len() as u16);
+            buf.put_u16_le(0); // extra field length
+            buf.put_u16_le(0); // comment length
+            buf.put_u16_le(0); // disk number start
+            buf.put_u16_le(0); // internal attributes
+            buf.put_u32_le(0); // external attributes
+            buf.put_u32_le(entry.offset);
+            buf.put_slice(entry.name.as_bytes());
+        }
+        let directory_size = u32::try_from(buf.len()).map_err(|_| too_large())?;
+
+        buf.put_u32_le(END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+        buf.put_u16_le(0); // number of this disk
+        buf.put_u16_le(0); // disk where central directory starts
+        buf.put_u16_le(entry_count);
+        buf.put_u16_le(entry_count);
+        buf.put_u32_le(directory_size);
+        buf.put_u32_le(directory_offset);
+        buf.put_u16_le(0); // comment length
+
+        Ok(buf.freeze())
+    }
+}
+
+fn too_large() -> io::Error {
+    io::Error::other("bundle exceeds the 4 GiB ZIP limit")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::io::{Cursor, Read};
+
+    use crate::common::infrastructure::storage::{
+        ObjectMetadata, ObjectStream, PresignedPut, StorageError, UploadPolicy,
+    };
+
+    struct InMemoryStorage;
+
+    /// Operations the archive never performs.
+    fn unsupported(operation: &str) -> StorageError {
+        StorageError::S3Error(format!(
+            "{} is not supported by the test storage",
+            operation
+        ))
+    }
+
+    #[async_trait]
+    impl StorageClient for InMemoryStorage {
+        async fn generate_presigned_get_url(
+            &self,
+            _object_key: &str,
+            _expires_in_secs: u64,
+        ) -> Result<String, StorageError> {
+            Err(unsupported("generate_presigned_get_url"))
+        }
+
+        async fn generate_presigned_put(
+            &self,
+            _object_key: &str,
+            _policy: &UploadPolicy,
+        ) -> Result<PresignedPut, StorageError> {
+            Err(unsupported("generate_presigned_put"))
+        }
+
+        async fn list_objects(&self, _prefix: &str) -> Result<Vec<String>, StorageError> {
+            Err(unsupported("list_objects"))
+        }
+
+        async fn get_object(&self, object_key: &str) -> Result<ObjectStream, StorageError> {
+            if object_key == "missing.txt" {
+                return Err(StorageError::S3Error("NoSuchKey".to_string()));
+            }
+            let chunks = vec![
+                Ok(Bytes::from(format!("content of {}", object_key))),
+                Ok(Bytes::from_static(b" - second chunk")),
+            ];
+            Ok(Box::pin(stream::iter(chunks)))
+        }
+
+        async fn head_object(&self, _object_key: &str) -> Result<ObjectMetadata, StorageError> {
+            Err(unsupported("head_object"))
+        }
+
+        async fn delete_object(&self, _object_key: &str) -> Result<(), StorageError> {
+            Err(unsupported("delete_object"))
+        }
+
+        async fn copy_object(
+            &self,
+            _source_key: &str,
+            _destination_key: &str,
+        ) -> Result<(), StorageError> {
+            Err(unsupported("copy_object"))
+        }
+
+        async fn check_health(&self) -> Result<(), StorageError> {
+            Ok(())
+        }
+    }
+
+    async fn collect(keys: &[&str]) -> Result<Vec<u8>, io::Error> {
+        let keys = keys.iter().map(|k| k.to_string()).collect();
+        let mut archive = Box::pin(stream_zip(Arc::new(InMemoryStorage), keys));
+        let mut bytes = Vec::new();
+        while let Some(chunk) = archive.next().await {
+            bytes.extend_from_slice(&chunk?);
+        }
+        Ok(bytes)
+    }
+
+    #[tokio::test]
+    async fn test_archive_is_readable() {
+        let bytes = collect(&["press/logo.png", "press/bio.txt"]).await.unwrap();
+
+        let mut archive = ::zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 2);
+
+        let mut content = String::new();
+        archive
+            .by_name("press/bio.txt")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "content of press/bio.txt - second chunk");
+    }
+
+    #[tokio::test]
+    async fn test_empty_archive() {
+        let bytes = collect(&[]).await.unwrap();
+        let archive = ::zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_missing_object_fails_stream() {
+        assert!(collect(&["press/logo.png", "missing.txt"]).await.is_err());
+    }
+}
@@ -0,0 +1,194 @@
+use axum::{
+    Json,
+    body::Body,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+
+use super::archive::stream_zip;
+use crate::common::{AppError, AppResult, AppState, auth::AdminAuth};
+
+pub const BUNDLE_TOKEN_AUDIENCE: &str = "bundle";
+
+const DEFAULT_EXPIRATION_SECS: u64 = 7 * 24 * 3600;
+
+fn default_expiration() -> u64 {
+    DEFAULT_EXPIRATION_SECS
+}
+
+struct BundleName(String);
+
+impl TryFrom<String> for BundleName {
+    type Error = String;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if s.is_empty() || s.len() > 100 {
+            Err("must be between 1 and 100 characters".to_string())
+        } else {
+            Ok(BundleName(s))
+        }
+    }
+}
+
+struct ExpiresIn(u64);
+
+impl TryFrom<u64> for ExpiresIn {
+    type Error = String;
+    fn try_from(n: u64) -> Result<Self, Self::Error> {
+        if !(60..=30 * 24 * 3600).contains(&n) {
+            Err("must be between 60 seconds and 30 days".to_string())
+        } else {
+            Ok(ExpiresIn(n))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateBundleTokenInput {
+    bundle: String,
+    #[serde(default = "default_expiration")]
+    expires_in: u64,
+}
+
+struct CreateBundleToken {
+    bundle: BundleName,
+    expires_in: ExpiresIn,
+}
+
+impl TryFrom<CreateBundleTokenInput> for CreateBundleToken {
+    type Error = AppError;
+    fn try_from(input: CreateBundleTokenInput) -> Result<Self, Self::Error> {
+        Ok(CreateBundleToken {
+            bundle: BundleName::try_from(input.bundle)
+                .map_err(|e| AppError::Validation(format!("bundle: {e}")))?,
+            expires_in: ExpiresIn::try_from(input.expires_in)
+                .map_err(|e| AppError::Validation(format!("expires_in: {e}")))?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleClaims {
+    bundle: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BundleTokenResponse {
+    pub token: String,
+    pub expires_at: String,
+}
+
+#[tracing::instrument(skip(state, input), fields(bundle = %input.bundle))]
+pub(super) async fn create_bundle_token_handler(
+    _admin: AdminAuth,
+    State(state): State<AppState>,
+    Json(input): Json<CreateBundleTokenInput>,
+) -> AppResult<(StatusCode, Json<BundleTokenResponse>)> {
+    let params = CreateBundleToken::try_from(input)?;
+
+    if state.bundles.get(&params.bundle.0).is_none() {
+        return Err(AppError::NotFound(format!(
+            "bundle '{}' is not declared in the manifest",
+            params.bundle.0
+        )));
+    }
+
+    let expires_at = chrono::Utc::now() + chrono::Duration::seconds(params.expires_in.0 as i64);
+    let token = state.signer.sign(
+        BUNDLE_TOKEN_AUDIENCE,
+        &BundleClaims {
+            bundle: params.bundle.0,
+        },
+        expires_at.timestamp(),
+    );
+
+    tracing::info!("Bundle token issued");
+
+    Ok((
+        StatusCode::CREATED,
+        Json(BundleTokenResponse {
+            token,
+            expires_at: expires_at.to_rfc3339(),
+        }),
+    ))
+}
+
+#[tracing::instrument(skip_all)]
+pub(super) async fn download_bundle_handler(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> AppResult<Response> {
+    let claims: BundleClaims = state
+        .signer
+        .verify(BUNDLE_TOKEN_AUDIENCE, &token)
+        .map_err(|e| {
+            tracing::info!("Rejected bundle token: {}", e);
+            AppError::NotFound("bundle not found or link expired".to_string())
+        })?;
+
+    let definition = state
+        .bundles
+        .get(&claims.bundle)
+        .ok_or_else(|| AppError::NotFound("bundle not found or link expired".to_string()))?;
+
+    let keys = definition.resolve_keys(state.storage.as_ref()).await?;
+    if keys.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "bundle '{}' has no objects",
+            claims.bundle
+        )));
+    }
+
+    tracing::info!(bundle = %claims.bundle, objects = keys.len(), "Streaming bundle");
+
+    let filename = definition.filename(&claims.bundle).replace('"', "");
+    let body = Body::from_stream(stream_zip(state.storage.clone(), keys));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_input() -> CreateBundleTokenInput {
+        CreateBundleTokenInput {
+            bundle: "press-kit".to_string(),
+            expires_in: 3600,
+        }
+    }
+
+    #[test]
+    fn test_valid_input() {
+        assert!(CreateBundleToken::try_from(valid_input()).is_ok());
+    }
+
+    #[test]
+    fn test_empty_bundle_name() {
+        let input = CreateBundleTokenInput {
+            bundle: "".to_string(),
+            ..valid_input()
+        };
+        assert!(CreateBundleToken::try_from(input).is_err());
+    }
+
+    #[test]
+    fn test_expiration_too_long() {
+        let input = CreateBundleTokenInput {
+            expires_in: 31 * 24 * 3600,
+            ..valid_input()
+        };
+        assert!(CreateBundleToken::try_from(input).is_err());
+    }
+}
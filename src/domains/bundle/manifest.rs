@@ -0,0 +1,69 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::{collections::HashMap, path::Path};
+
+use crate::common::infrastructure::storage::{StorageClient, StorageError};
+
+/// Allowlist of downloadable bundles, loaded from `BUNDLE_MANIFEST_PATH`.
+///
+/// Only bundles declared here can be referenced by a bundle token, and the
+/// manifest is consulted again at download time so removing an entry revokes
+/// every link already handed out.
+#[derive(Debug, Default, Deserialize)]
+pub struct BundleManifest {
+    #[serde(default)]
+    bundles: HashMap<String, BundleDefinition>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BundleDefinition {
+    #[serde(default)]
+    objects: Vec<String>,
+    #[serde(default)]
+    prefixes: Vec<String>,
+    filename: Option<String>,
+}
+
+impl BundleManifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read bundle manifest {}", path.display()))?;
+        Self::from_json(&content)
+            .with_context(|| format!("Invalid bundle manifest {}", path.display()))
+    }
+
+    pub fn from_json(content: &str) -> Result<Self> {
+        Ok(serde_json::from_str(content)?)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&BundleDefinition> {
+        self.bundles.get(name)
+    }
+}
+
+impl BundleDefinition {
+    pub fn filename(&self, bundle_name: &str) -> String {
+        self.filename
+            .clone()
+            .unwrap_or_else(|| format!("{}.zip", bundle_name))
+    }
+
+    /// Expands the listed objects and prefixes into a deduplicated key list.
+    pub async fn resolve_keys(
+        &self,
+        storage: &dyn StorageClient,
+    ) -> Result<Vec<String>, StorageError> {
+        let mut keys = self.objects.clone();
+
+        for prefix in &self.prefixes {
+            for key in storage.list_objects(prefix).await? {
+                // Skip "directory" placeholder objects
+                if !key.ends_with('/') && !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+
+        Ok(keys)
+    }
+}
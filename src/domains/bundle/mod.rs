@@ -0,0 +1,7 @@
+mod archive;
+mod handler;
+pub mod manifest;
+mod routes;
+
+pub use manifest::BundleManifest;
+pub use routes::bundle_routes as routes;
@@ -0,0 +1,15 @@
+use axum::{
+    Router,
+    routing::{get, post},
+};
+
+use crate::{
+    common::AppState,
+    domains::bundle::handler::{create_bundle_token_handler, download_bundle_handler},
+};
+
+pub fn bundle_routes() -> Router<AppState> {
+    Router::new()
+        .route("/bundle/token", post(create_bundle_token_handler))
+        .route("/bundle/{token}", get(download_bundle_handler))
+}
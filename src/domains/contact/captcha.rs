@@ -0,0 +1,234 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::{net::IpAddr, sync::Arc};
+
+use crate::common::errors::{AppError, AppResult};
+
+pub const TURNSTILE_VERIFY_URL: &str = "https://challenges.cloudflare.com/turnstile/v0/siteverify";
+pub const HCAPTCHA_VERIFY_URL: &str = "https://api.hcaptcha.com/siteverify";
+
+/// Checks the token a CAPTCHA widget handed to the visitor.
+#[async_trait]
+pub trait CaptchaVerifier: Send + Sync {
+    /// Provider name used in logs and metrics.
+    fn name(&self) -> &str;
+
+    /// `Ok(false)` when the provider rejects the token, errors are reserved
+    /// for the provider being unreachable or answering garbage.
+    async fn verify(&self, token: &str, remote_ip: Option<IpAddr>) -> AppResult<bool>;
+}
+
+#[derive(Clone)]
+pub enum CaptchaConfig {
+    Turnstile {
+        secret: String,
+        verify_url: String,
+    },
+    HCaptcha {
+        secret: String,
+        verify_url: String,
+        /// Optional, makes hCaptcha reject tokens issued for another site.
+        sitekey: Option<String>,
+    },
+}
+
+pub fn build_captcha(
+    config: &CaptchaConfig,
+    http_client: reqwest::Client,
+) -> Arc<dyn CaptchaVerifier> {
+    match config {
+        CaptchaConfig::Turnstile { secret, verify_url } => Arc::new(TurnstileVerifier::new(
+            http_client,
+            secret.clone(),
+            verify_url.clone(),
+        )),
+        CaptchaConfig::HCaptcha {
+            secret,
+            verify_url,
+            sitekey,
+        } => Arc::new(HCaptchaVerifier::new(
+            http_client,
+            secret.clone(),
+            verify_url.clone(),
+            sitekey.clone(),
+        )),
+    }
+}
+
+/// Response shared by the Turnstile and hCaptcha `siteverify` endpoints.
+#[derive(Debug, Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+async fn siteverify(
+    client: &reqwest::Client,
+    provider: &str,
+    url: &str,
+    form: &[(&str, &str)],
+) -> AppResult<bool> {
+    let response: SiteVerifyResponse = client
+        .post(url)
+        .form(form)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| AppError::Captcha(format!("{} unreachable: {}", provider, e)))?
+        .json()
+        .await
+        .map_err(|e| AppError::Captcha(format!("{} invalid response: {}", provider, e)))?;
+
+    if !response.success {
+        tracing::info!(
+            provider,
+            error_codes = ?response.error_codes,
+            "CAPTCHA token rejected"
+        );
+    }
+    Ok(response.success)
+}
+
+/// Cloudflare Turnstile.
+pub struct TurnstileVerifier {
+    client: reqwest::Client,
+    secret: String,
+    verify_url: String,
+}
+
+impl TurnstileVerifier {
+    pub fn new(client: reqwest::Client, secret: String, verify_url: String) -> Self {
+        Self {
+            client,
+            secret,
+            verify_url,
+        }
+    }
+}
+
+#[async_trait]
+impl CaptchaVerifier for TurnstileVerifier {
+    fn name(&self) -> &str {
+        "turnstile"
+    }
+
+    async fn verify(&self, token: &str, remote_ip: Option<IpAddr>) -> AppResult<bool> {
+        let remote_ip = remote_ip.map(|ip| ip.to_string());
+        let mut form = vec![("secret", self.secret.as_str()), ("response", token)];
+        if let Some(ip) = &remote_ip {
+            form.push(("remoteip", ip));
+        }
+        siteverify(&self.client, self.name(), &self.verify_url, &form).await
+    }
+}
+
+pub struct HCaptchaVerifier {
+    client: reqwest::Client,
+    secret: String,
+    verify_url: String,
+    sitekey: Option<String>,
+}
+
+impl HCaptchaVerifier {
+    pub fn new(
+        client: reqwest::Client,
+        secret: String,
+        verify_url: String,
+        sitekey: Option<String>,
+    ) -> Self {
+        Self {
+            client,
+            secret,
+            verify_url,
+            sitekey,
+        }
+    }
+}
+
+#[async_trait]
+impl CaptchaVerifier for HCaptchaVerifier {
+    fn name(&self) -> &str {
+        "hcaptcha"
+    }
+
+    async fn verify(&self, token: &str, remote_ip: Option<IpAddr>) -> AppResult<bool> {
+        let remote_ip = remote_ip.map(|ip| ip.to_string());
+        let mut form = vec![("secret", self.secret.as_str()), ("response", token)];
+        if let Some(ip) = &remote_ip {
+            form.push(("remoteip", ip));
+        }
+        if let Some(sitekey) = &self.sitekey {
+            form.push(("sitekey", sitekey));
+        }
+        siteverify(&self.client, self.name(), &self.verify_url, &form).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Form, Json, Router, routing::post};
+    use std::collections::HashMap;
+
+    /// Fake `siteverify` accepting only the `"valid"` token sent with the
+    /// `"secret"` secret.
+    async fn stub_siteverify() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route(
+            "/siteverify",
+            post(|Form(form): Form<HashMap<String, String>>| async move {
+                let success = form.get("secret").map(String::as_str) == Some("secret")
+                    && form.get("response").map(String::as_str) == Some("valid")
+                    && form.get("remoteip").map(String::as_str) == Some("203.0.113.7");
+                Json(serde_json::json!({
+                    "success": success,
+                    "error-codes": if success { vec![] } else { vec!["invalid-input-response"] }
+                }))
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/siteverify", addr)
+    }
+
+    const IP: Option<IpAddr> = Some(IpAddr::V4(std::net::Ipv4Addr::new(203, 0, 113, 7)));
+
+    #[tokio::test]
+    async fn test_turnstile_verification() {
+        let verifier = TurnstileVerifier::new(
+            reqwest::Client::new(),
+            "secret".to_string(),
+            stub_siteverify().await,
+        );
+
+        assert!(verifier.verify("valid", IP).await.unwrap());
+        assert!(!verifier.verify("forged", IP).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_hcaptcha_verification() {
+        let verifier = HCaptchaVerifier::new(
+            reqwest::Client::new(),
+            "secret".to_string(),
+            stub_siteverify().await,
+            Some("site-key".to_string()),
+        );
+
+        assert!(verifier.verify("valid", IP).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_provider_is_an_error() {
+        let verifier = TurnstileVerifier::new(
+            reqwest::Client::new(),
+            "secret".to_string(),
+            "http://127.0.0.1:1/siteverify".to_string(),
+        );
+
+        assert!(matches!(
+            verifier.verify("valid", IP).await,
+            Err(AppError::Captcha(_))
+        ));
+    }
+}
@@ -0,0 +1,316 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Mutex,
+    time::Duration,
+};
+
+/// Above this many tracked emails, the ones without recent submissions are
+/// forgotten.
+const MAX_TRACKED_EMAILS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateAction {
+    /// Delivers the notification marked as a duplicate of the first one.
+    Flag,
+    /// Accepts the submission without notifying anyone.
+    Suppress,
+}
+
+impl DuplicateAction {
+    /// Metric label.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DuplicateAction::Flag => "flag",
+            DuplicateAction::Suppress => "suppress",
+        }
+    }
+}
+
+impl std::str::FromStr for DuplicateAction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "flag" => Ok(DuplicateAction::Flag),
+            "suppress" => Ok(DuplicateAction::Suppress),
+            other => Err(format!("unknown duplicate action '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DuplicateConfig {
+    pub window: Duration,
+    /// Similarity from 0 to 1 above which two messages are the same, compared
+    /// on their character trigrams.
+    pub similarity_threshold: f64,
+    pub action: DuplicateAction,
+}
+
+#[derive(Debug)]
+struct Submission {
+    at: i64,
+    request_id: String,
+    trigrams: HashSet<[char; 3]>,
+    normalized: String,
+    /// Reserved but not queued yet.
+    pending: bool,
+}
+
+/// Spots visitors sending the same message again, with a typo fixed or a
+/// line added, from the same email within the window.
+pub struct DuplicateDetector {
+    config: DuplicateConfig,
+    recent: Mutex<HashMap<String, VecDeque<Submission>>>,
+}
+
+impl DuplicateDetector {
+    pub fn new(config: DuplicateConfig) -> Self {
+        Self {
+            config,
+            recent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &DuplicateConfig {
+        &self.config
+    }
+
+    /// Request ID of an earlier submission this one duplicates. Otherwise
+    /// the submission is reserved as an original, in the same locked step so
+    /// concurrent resubmissions see it, until [`Self::confirm`] or
+    /// [`Self::release`].
+    pub fn reserve(
+        &self,
+        email: &str,
+        message: &str,
+        request_id: &str,
+        now: i64,
+    ) -> Option<String> {
+        let window_start = now - self.config.window.as_secs() as i64;
+        let normalized = normalize(message);
+        let trigrams = trigrams(&normalized);
+
+        let mut recent = self
+            .recent
+            .lock()
+            .expect("duplicate detector lock poisoned");
+        let submissions = recent.entry(email.trim().to_lowercase()).or_default();
+        while submissions.front().is_some_and(|s| s.at <= window_start) {
+            submissions.pop_front();
+        }
+
+        if let Some(original) = submissions.iter().find(|previous| {
+            previous.normalized == normalized
+                || similarity(&previous.trigrams, &trigrams) >= self.config.similarity_threshold
+        }) {
+            return Some(original.request_id.clone());
+        }
+
+        submissions.push_back(Submission {
+            at: now,
+            request_id: request_id.to_string(),
+            trigrams,
+            normalized,
+            pending: true,
+        });
+
+        if recent.len() > MAX_TRACKED_EMAILS {
+            recent.retain(|_, submissions| submissions.back().is_some_and(|s| s.at > window_start));
+        }
+        None
+    }
+
+    /// Keeps a reserved original once it's safely queued.
+    pub fn confirm(&self, email: &str, request_id: &str) {
+        let mut recent = self
+            .recent
+            .lock()
+            .expect("duplicate detector lock poisoned");
+        if let Some(submission) = recent
+            .get_mut(&email.trim().to_lowercase())
+            .and_then(|submissions| submissions.iter_mut().find(|s| s.request_id == request_id))
+        {
+            submission.pending = false;
+        }
+    }
+
+    /// Forgets a reserved original that couldn't be queued, so its retry
+    /// isn't flagged as a duplicate of it.
+    pub fn release(&self, email: &str, request_id: &str) {
+        let mut recent = self
+            .recent
+            .lock()
+            .expect("duplicate detector lock poisoned");
+        if let Some(submissions) = recent.get_mut(&email.trim().to_lowercase()) {
+            submissions.retain(|s| !(s.pending && s.request_id == request_id));
+        }
+    }
+}
+
+/// Lowercase words, punctuation and spacing differences don't count.
+fn normalize(message: &str) -> String {
+    message
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn trigrams(normalized: &str) -> HashSet<[char; 3]> {
+    let chars = normalized.chars().collect::<Vec<_>>();
+    chars.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+}
+
+/// Jaccard index of the trigram sets, 0 when either is empty.
+fn similarity(a: &HashSet<[char; 3]>, b: &HashSet<[char; 3]>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> DuplicateDetector {
+        DuplicateDetector::new(DuplicateConfig {
+            window: Duration::from_secs(3600),
+            similarity_threshold: 0.8,
+            action: DuplicateAction::Flag,
+        })
+    }
+
+    /// Reserves then confirms originals, like the contact handler.
+    fn submit(
+        detector: &DuplicateDetector,
+        email: &str,
+        message: &str,
+        request_id: &str,
+        now: i64,
+    ) -> Option<String> {
+        let original = detector.reserve(email, message, request_id, now);
+        if original.is_none() {
+            detector.confirm(email, request_id);
+        }
+        original
+    }
+
+    const MESSAGE: &str = "Bonjour, nous aimerions réaliser une vidéo de présentation \
+        pour notre nouvelle collection. Pouvez-vous nous envoyer un devis ?";
+
+    #[test]
+    fn test_resubmission_is_a_duplicate_of_the_first() {
+        let detector = detector();
+
+        assert_eq!(
+            submit(&detector, "john@example.com", MESSAGE, "req-1", 1000),
+            None
+        );
+        assert_eq!(
+            submit(
+                &detector,
+                " John@Example.com",
+                &MESSAGE.to_uppercase(),
+                "req-2",
+                1060
+            ),
+            Some("req-1".to_string())
+        );
+        let edited = MESSAGE.replace("Pouvez-vous", "Pourriez-vous");
+        assert_eq!(
+            submit(&detector, "john@example.com", &edited, "req-3", 1120),
+            Some("req-1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_different_messages_senders_or_times_are_not_duplicates() {
+        let detector = detector();
+        submit(&detector, "john@example.com", MESSAGE, "req-1", 1000);
+
+        assert_eq!(
+            submit(&detector, "jane@example.com", MESSAGE, "req-2", 1010),
+            None
+        );
+        assert_eq!(
+            submit(
+                &detector,
+                "john@example.com",
+                "Pour la presse : interview ?",
+                "req-3",
+                1020
+            ),
+            None
+        );
+        assert_eq!(
+            submit(&detector, "john@example.com", MESSAGE, "req-4", 1000 + 3601),
+            None
+        );
+    }
+
+    #[test]
+    fn test_short_messages_must_match_exactly() {
+        let detector = detector();
+        submit(&detector, "john@example.com", "Hi!", "req-1", 1000);
+
+        assert_eq!(
+            submit(&detector, "john@example.com", "hi", "req-2", 1010),
+            Some("req-1".to_string())
+        );
+        assert_eq!(
+            submit(&detector, "john@example.com", "yo", "req-3", 1020),
+            None
+        );
+    }
+
+    #[test]
+    fn test_released_submissions_are_not_remembered() {
+        let detector = detector();
+
+        // Reserved but never queued, e.g. the outbox write failed
+        assert_eq!(
+            detector.reserve("john@example.com", MESSAGE, "req-1", 1000),
+            None
+        );
+        detector.release("john@example.com", "req-1");
+
+        assert_eq!(
+            submit(&detector, "john@example.com", MESSAGE, "req-2", 1010),
+            None
+        );
+        // Confirmed originals are never released
+        detector.release("john@example.com", "req-2");
+        assert_eq!(
+            submit(&detector, "john@example.com", MESSAGE, "req-3", 1020),
+            Some("req-2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_concurrent_resubmissions_have_a_single_original() {
+        let detector = detector();
+        let barrier = std::sync::Barrier::new(8);
+
+        let originals = std::thread::scope(|scope| {
+            let handles = (0..8)
+                .map(|i| {
+                    let (detector, barrier) = (&detector, &barrier);
+                    scope.spawn(move || {
+                        barrier.wait();
+                        detector.reserve("john@example.com", MESSAGE, &format!("req-{i}"), 1000)
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .filter(Option::is_none)
+                .count()
+        });
+
+        assert_eq!(originals, 1);
+    }
+}
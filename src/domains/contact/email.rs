@@ -0,0 +1,377 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+/// Throwaway inbox providers, matched with their subdomains.
+const DISPOSABLE_DOMAINS: &[&str] = &[
+    "10minutemail.com",
+    "33mail.com",
+    "burnermail.io",
+    "discard.email",
+    "dispostable.com",
+    "emailondeck.com",
+    "fakeinbox.com",
+    "getnada.com",
+    "guerrillamail.com",
+    "guerrillamail.net",
+    "guerrillamailblock.com",
+    "jetable.org",
+    "mailcatch.com",
+    "maildrop.cc",
+    "mailinator.com",
+    "mailnesia.com",
+    "mintemail.com",
+    "mohmal.com",
+    "mytemp.email",
+    "sharklasers.com",
+    "spamgourmet.com",
+    "temp-mail.org",
+    "tempail.com",
+    "tempmail.com",
+    "tempmail.dev",
+    "tempr.email",
+    "throwawaymail.com",
+    "trashmail.com",
+    "trashmail.de",
+    "yopmail.com",
+    "yopmail.fr",
+    "yopmail.net",
+];
+
+const MAX_ADDRESS_LENGTH: usize = 254;
+const MAX_LOCAL_PART_LENGTH: usize = 64;
+const MAX_DOMAIN_LENGTH: usize = 253;
+const MAX_LABEL_LENGTH: usize = 63;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum EmailError {
+    #[error("must contain an @ between the local part and the domain")]
+    MissingAt,
+
+    #[error("must be at most 254 characters")]
+    TooLong,
+
+    #[error("local part must be between 1 and 64 characters")]
+    LocalPartLength,
+
+    #[error("quoted local parts are not accepted")]
+    QuotedLocalPart,
+
+    #[error("local part contains invalid characters")]
+    LocalPartCharacters,
+
+    #[error("local part can't start or end with a dot or contain consecutive dots")]
+    LocalPartDots,
+
+    #[error("IP address literals are not accepted")]
+    AddressLiteral,
+
+    #[error("domain is not a valid internationalized domain name")]
+    InvalidDomain,
+
+    #[error("domain must be at most 253 characters with labels of 1 to 63")]
+    DomainLength,
+
+    #[error("domain labels may only contain letters, digits and inner hyphens")]
+    DomainCharacters,
+
+    #[error("domain must end with a top-level domain")]
+    MissingTld,
+
+    #[error("disposable email addresses are not accepted")]
+    Disposable,
+
+    #[error("email address is not accepted")]
+    Blocked,
+}
+
+/// Checks `address` against the RFC 5321 mailbox syntax and normalizes its
+/// domain to lowercase ASCII, internationalized domains becoming punycode.
+///
+/// Only dot-atom local parts are accepted, as sent by every real-world form.
+pub fn normalize(address: &str) -> Result<String, EmailError> {
+    let address = address.trim();
+    let (local, domain) = address.rsplit_once('@').ok_or(EmailError::MissingAt)?;
+
+    if local.starts_with('"') {
+        return Err(EmailError::QuotedLocalPart);
+    }
+    if local.is_empty() || local.len() > MAX_LOCAL_PART_LENGTH {
+        return Err(EmailError::LocalPartLength);
+    }
+    if !local.chars().all(is_atext_or_dot) {
+        return Err(EmailError::LocalPartCharacters);
+    }
+    if local.starts_with('.') || local.ends_with('.') || local.contains("..") {
+        return Err(EmailError::LocalPartDots);
+    }
+
+    if domain.starts_with('[') {
+        return Err(EmailError::AddressLiteral);
+    }
+    let domain = idna::domain_to_ascii(domain).map_err(|_| EmailError::InvalidDomain)?;
+    let labels = domain.split('.').collect::<Vec<_>>();
+    if domain.is_empty()
+        || domain.len() > MAX_DOMAIN_LENGTH
+        || labels
+            .iter()
+            .any(|label| label.is_empty() || label.len() > MAX_LABEL_LENGTH)
+    {
+        return Err(EmailError::DomainLength);
+    }
+    if !labels.iter().all(|label| is_ldh_label(label)) {
+        return Err(EmailError::DomainCharacters);
+    }
+    match labels.last() {
+        Some(tld) if labels.len() >= 2 && !tld.chars().all(|c| c.is_ascii_digit()) => {}
+        _ => return Err(EmailError::MissingTld),
+    }
+
+    let address = format!("{}@{}", local, domain);
+    if address.len() > MAX_ADDRESS_LENGTH {
+        return Err(EmailError::TooLong);
+    }
+    Ok(address)
+}
+
+/// RFC 5322 `atext`, plus the dots separating atoms.
+fn is_atext_or_dot(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~.".contains(c)
+}
+
+fn is_ldh_label(label: &str) -> bool {
+    label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+}
+
+#[derive(Debug, Default)]
+struct Blocklist {
+    /// Domains, matched with their subdomains, and full addresses.
+    entries: HashSet<String>,
+    modified: Option<SystemTime>,
+}
+
+/// Rejects disposable domains and the addresses or domains listed in the
+/// blocklist file, which is reloaded when it changes.
+pub struct EmailPolicy {
+    disposable: HashSet<String>,
+    blocklist_path: Option<PathBuf>,
+    blocklist: RwLock<Blocklist>,
+}
+
+impl EmailPolicy {
+    /// Fails when the blocklist file can't be read, later reload failures
+    /// keep the previous list.
+    pub fn new(blocklist_path: Option<PathBuf>) -> anyhow::Result<Self> {
+        let policy = Self {
+            disposable: DISPOSABLE_DOMAINS.iter().map(|d| d.to_string()).collect(),
+            blocklist_path,
+            blocklist: RwLock::new(Blocklist::default()),
+        };
+        policy.reload()?;
+        Ok(policy)
+    }
+
+    /// Re-reads the blocklist file if it was modified, returns whether it was.
+    pub fn reload(&self) -> anyhow::Result<bool> {
+        let Some(path) = &self.blocklist_path else {
+            return Ok(false);
+        };
+        let modified = std::fs::metadata(path)?.modified().ok();
+        if modified.is_some()
+            && modified
+                == self
+                    .blocklist
+                    .read()
+                    .expect("blocklist lock poisoned")
+                    .modified
+        {
+            return Ok(false);
+        }
+
+        let entries = read_blocklist(path)?;
+        tracing::info!(
+            path = %path.display(),
+            entries = entries.len(),
+            "Loaded email blocklist"
+        );
+        *self.blocklist.write().expect("blocklist lock poisoned") = Blocklist { entries, modified };
+        Ok(true)
+    }
+
+    /// Checks the blocklist file for changes every `interval`, reading it on
+    /// the blocking pool.
+    pub fn spawn_reloader(self: Arc<Self>, interval: Duration) {
+        if self.blocklist_path.is_none() {
+            return;
+        }
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let policy = self.clone();
+                let result = tokio::task::spawn_blocking(move || policy.reload())
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|result| result);
+                if let Err(e) = result {
+                    tracing::error!(
+                        "Failed to reload email blocklist, keeping the previous one: {}",
+                        e
+                    );
+                }
+            }
+        });
+    }
+
+    /// Checks a [`normalize`]d address.
+    pub fn check(&self, address: &str) -> Result<(), EmailError> {
+        let address = address.to_lowercase();
+        let domain = address.rsplit_once('@').map_or("", |(_, domain)| domain);
+
+        if domain_or_parent(domain).any(|d| self.disposable.contains(d)) {
+            return Err(EmailError::Disposable);
+        }
+        let blocklist = self.blocklist.read().expect("blocklist lock poisoned");
+        if blocklist.entries.contains(&address)
+            || domain_or_parent(domain).any(|d| blocklist.entries.contains(d))
+        {
+            return Err(EmailError::Blocked);
+        }
+        Ok(())
+    }
+}
+
+/// `a.b.example.com`, `b.example.com`, `example.com` and `com`.
+fn domain_or_parent(domain: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(Some(domain), |d| {
+        d.split_once('.').map(|(_, parent)| parent)
+    })
+}
+
+/// One domain or address per line, `#` starts a comment. Internationalized
+/// domains are normalized like the submitted addresses, invalid entries are
+/// skipped with a warning.
+fn read_blocklist(path: &Path) -> anyhow::Result<HashSet<String>> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or_default().trim()))
+        .filter(|(_, entry)| !entry.is_empty())
+        .filter_map(|(line, entry)| {
+            let normalized = match entry.split_once('@') {
+                Some(_) => normalize(entry).ok(),
+                None => idna::domain_to_ascii(entry).ok(),
+            };
+            if normalized.is_none() {
+                tracing::warn!(
+                    path = %path.display(),
+                    line,
+                    "Ignoring invalid email blocklist entry '{}'",
+                    entry
+                );
+            }
+            normalized
+        })
+        .map(|entry| entry.to_lowercase())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_addresses_are_normalized() {
+        assert_eq!(
+            normalize(" John.Doe+devis@Example.COM "),
+            Ok("John.Doe+devis@example.com".to_string())
+        );
+        assert_eq!(
+            normalize("jeanne@café.fr"),
+            Ok("jeanne@xn--caf-dma.fr".to_string())
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors_are_distinct() {
+        let cases = [
+            ("john.example.com", EmailError::MissingAt),
+            ("@example.com", EmailError::LocalPartLength),
+            (
+                &format!("{}@example.com", "a".repeat(65)),
+                EmailError::LocalPartLength,
+            ),
+            ("\"john doe\"@example.com", EmailError::QuotedLocalPart),
+            ("john doe@example.com", EmailError::LocalPartCharacters),
+            ("john..doe@example.com", EmailError::LocalPartDots),
+            ("john@[192.0.2.1]", EmailError::AddressLiteral),
+            ("john@exa_mple.com", EmailError::DomainCharacters),
+            ("john@-example.com", EmailError::DomainCharacters),
+            ("john@example..com", EmailError::DomainLength),
+            ("john@localhost", EmailError::MissingTld),
+            ("john@192.0.2.1", EmailError::MissingTld),
+            (
+                &format!("{}@{}com", "a".repeat(64), "b.".repeat(95)),
+                EmailError::TooLong,
+            ),
+        ];
+
+        for (address, error) in cases {
+            assert_eq!(normalize(address), Err(error), "{address}");
+        }
+    }
+
+    #[test]
+    fn test_disposable_domains_are_rejected() {
+        let policy = EmailPolicy::new(None).unwrap();
+
+        assert_eq!(
+            policy.check("john@mailinator.com"),
+            Err(EmailError::Disposable)
+        );
+        assert_eq!(
+            policy.check("john@eu.yopmail.com"),
+            Err(EmailError::Disposable)
+        );
+        assert_eq!(policy.check("john@gmail.com"), Ok(()));
+    }
+
+    #[test]
+    fn test_blocklist_is_reloaded_when_modified() {
+        let path = std::env::temp_dir().join(format!("blocklist-{}.txt", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "# competitors\nrival.example\nspammer@gmail.com\n").unwrap();
+        let policy = EmailPolicy::new(Some(path.clone())).unwrap();
+
+        assert_eq!(policy.check("boss@rival.example"), Err(EmailError::Blocked));
+        assert_eq!(
+            policy.check("boss@sales.rival.example"),
+            Err(EmailError::Blocked)
+        );
+        assert_eq!(policy.check("Spammer@gmail.com"), Err(EmailError::Blocked));
+        assert_eq!(policy.check("someone@gmail.com"), Ok(()));
+
+        std::fs::write(&path, "café.fr\n").unwrap();
+        // Make sure the modification time changes on coarse filesystems
+        let later = SystemTime::now() + Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert!(policy.reload().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(policy.check("boss@rival.example"), Ok(()));
+        assert_eq!(
+            policy.check(&normalize("jeanne@café.fr").unwrap()),
+            Err(EmailError::Blocked)
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::common::signing::Signer;
+
+pub const FORM_TOKEN_AUDIENCE: &str = "contact-form";
+
+#[derive(Debug, Clone)]
+pub struct FormGuardConfig {
+    /// Submissions sent sooner than this after the form was rendered are
+    /// considered automated.
+    pub min_fill_time: Duration,
+    pub token_ttl: Duration,
+    /// Treats submissions without a form token as bots. Off while the
+    /// frontend doesn't fetch tokens yet.
+    pub require_token: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FormClaims {
+    /// Milliseconds since the epoch at which the form was rendered.
+    issued_at: i64,
+}
+
+/// Why a submission was dropped, used as the metric label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BotSignal {
+    Honeypot,
+    MissingToken,
+    InvalidToken,
+    TooFast,
+}
+
+impl BotSignal {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BotSignal::Honeypot => "honeypot",
+            BotSignal::MissingToken => "missing_token",
+            BotSignal::InvalidToken => "invalid_token",
+            BotSignal::TooFast => "too_fast",
+        }
+    }
+}
+
+/// Issues the token embedded in the form when it's rendered, along with its
+/// expiration as a Unix timestamp.
+pub fn issue_form_token(signer: &Signer, config: &FormGuardConfig, now_ms: i64) -> (String, i64) {
+    let expires_at = now_ms / 1000 + config.token_ttl.as_secs() as i64;
+    let token = signer.sign(
+        FORM_TOKEN_AUDIENCE,
+        &FormClaims { issued_at: now_ms },
+        expires_at,
+    );
+    (token, expires_at)
+}
+
+/// Looks for signs of an automated submission: a filled honeypot, or a form
+/// token that's missing, forged, expired or used too soon after rendering.
+pub fn detect_bot(
+    signer: &Signer,
+    config: &FormGuardConfig,
+    honeypot: &str,
+    form_token: Option<&str>,
+    now_ms: i64,
+) -> Option<BotSignal> {
+    if !honeypot.trim().is_empty() {
+        return Some(BotSignal::Honeypot);
+    }
+
+    let Some(token) = form_token.filter(|t| !t.is_empty()) else {
+        return config.require_token.then_some(BotSignal::MissingToken);
+    };
+
+    let claims: FormClaims = match signer.verify_at(FORM_TOKEN_AUDIENCE, token, now_ms / 1000) {
+        Ok(claims) => claims,
+        Err(_) => return Some(BotSignal::InvalidToken),
+    };
+
+    let elapsed = now_ms.saturating_sub(claims.issued_at);
+    (elapsed < config.min_fill_time.as_millis() as i64).then_some(BotSignal::TooFast)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> FormGuardConfig {
+        FormGuardConfig {
+            min_fill_time: Duration::from_secs(3),
+            token_ttl: Duration::from_secs(3600),
+            require_token: true,
+        }
+    }
+
+    #[test]
+    fn test_human_submission_passes() {
+        let signer = Signer::new("secret");
+        let (token, _) = issue_form_token(&signer, &config(), 1_000_000);
+
+        let signal = detect_bot(&signer, &config(), "", Some(&token), 1_010_000);
+
+        assert_eq!(signal, None);
+    }
+
+    #[test]
+    fn test_bot_signals() {
+        let signer = Signer::new("secret");
+        let (token, _) = issue_form_token(&signer, &config(), 1_000_000);
+
+        assert_eq!(
+            detect_bot(&signer, &config(), "https://spam", Some(&token), 1_010_000),
+            Some(BotSignal::Honeypot)
+        );
+        assert_eq!(
+            detect_bot(&signer, &config(), "", Some(&token), 1_001_000),
+            Some(BotSignal::TooFast)
+        );
+        assert_eq!(
+            detect_bot(&signer, &config(), "", None, 1_010_000),
+            Some(BotSignal::MissingToken)
+        );
+        assert_eq!(
+            detect_bot(
+                &Signer::new("other"),
+                &config(),
+                "",
+                Some(&token),
+                1_010_000
+            ),
+            Some(BotSignal::InvalidToken)
+        );
+        // Expired an hour after being issued
+        assert_eq!(
+            detect_bot(&signer, &config(), "", Some(&token), 1_000_000 + 3_601_000),
+            Some(BotSignal::InvalidToken)
+        );
+    }
+
+    #[test]
+    fn test_token_is_optional_unless_required() {
+        let config = FormGuardConfig {
+            require_token: false,
+            ..config()
+        };
+        assert_eq!(
+            detect_bot(&Signer::new("secret"), &config, "", None, 0),
+            None
+        );
+    }
+}
@@ -1,8 +1,24 @@
-use axum::{Json, extract::State};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 
-use crate::common::{AppError, AppResult, AppState};
+use super::dedup::DuplicateAction;
+use super::email::{self, EmailPolicy};
+use super::guard;
+use super::outbox::QuarantinedEntry;
+use super::pow::Challenge;
+use super::service::{
+    ContactCategory, ContactSubmitted, EventMetadata, NotificationEvent, TemplateChannel, templates,
+};
+use super::spam;
+use crate::common::{
+    AppError, AppResult, AppState,
+    auth::AdminAuth,
+    middleware::{ClientIp, RequestId},
+};
 
 struct Name(String);
 
@@ -44,14 +60,13 @@ impl std::fmt::Display for PhoneNumber {
 
 struct Email(String);
 
-impl TryFrom<String> for Email {
-    type Error = String;
-    fn try_from(s: String) -> Result<Self, Self::Error> {
-        if is_valid_email(&s) {
-            Ok(Email(s))
-        } else {
-            Err("invalid email address".to_string())
-        }
+impl Email {
+    /// Normalized address, provided its syntax is valid and its domain is
+    /// accepted by `policy`.
+    fn parse(s: String, policy: &EmailPolicy) -> Result<Self, String> {
+        let address = email::normalize(&s).map_err(|e| e.to_string())?;
+        policy.check(&address).map_err(|e| e.to_string())?;
+        Ok(Email(address))
     }
 }
 
@@ -61,13 +76,6 @@ impl std::fmt::Display for Email {
     }
 }
 
-fn is_valid_email(s: &str) -> bool {
-    let mut parts = s.splitn(2, '@');
-    let local = parts.next().unwrap_or("");
-    let domain = parts.next().unwrap_or("");
-    !local.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.')
-}
-
 struct Message(String);
 
 impl TryFrom<String> for Message {
@@ -94,9 +102,26 @@ pub(crate) struct ContactFormInput {
     number: String,
     email: String,
     message: String,
+    #[serde(default)]
+    category: ContactCategory,
+    /// Honeypot, hidden from humans by the frontend so only bots fill it.
+    #[serde(default)]
+    website: String,
+    /// Token from `GET /contact/token`, fetched when the form is rendered.
+    #[serde(default)]
+    form_token: Option<String>,
+    /// Challenge from `GET /contact/challenge` and the client's solution.
+    #[serde(default)]
+    pow_challenge: Option<String>,
+    #[serde(default)]
+    pow_solution: Option<String>,
+    /// Token from the Turnstile or hCaptcha widget, when one is configured.
+    #[serde(default, alias = "cf-turnstile-response", alias = "h-captcha-response")]
+    captcha_token: Option<String>,
 }
 
 struct ContactForm {
+    category: ContactCategory,
     first_name: Name,
     last_name: Name,
     number: PhoneNumber,
@@ -104,17 +129,17 @@ struct ContactForm {
     message: Message,
 }
 
-impl TryFrom<ContactFormInput> for ContactForm {
-    type Error = AppError;
-    fn try_from(input: ContactFormInput) -> Result<Self, Self::Error> {
+impl ContactForm {
+    fn parse(input: ContactFormInput, email_policy: &EmailPolicy) -> AppResult<Self> {
         Ok(ContactForm {
+            category: input.category,
             first_name: Name::try_from(input.first_name)
                 .map_err(|e| AppError::Validation(format!("first_name: {e}")))?,
             last_name: Name::try_from(input.last_name)
                 .map_err(|e| AppError::Validation(format!("last_name: {e}")))?,
             number: PhoneNumber::try_from(input.number)
                 .map_err(|e| AppError::Validation(format!("number: {e}")))?,
-            email: Email::try_from(input.email)
+            email: Email::parse(input.email, email_policy)
                 .map_err(|e| AppError::Validation(format!("email: {e}")))?,
             message: Message::try_from(input.message)
                 .map_err(|e| AppError::Validation(format!("message: {e}")))?,
@@ -125,38 +150,331 @@ impl TryFrom<ContactFormInput> for ContactForm {
 #[tracing::instrument(skip(state, input), fields(email = %input.email))]
 pub(super) async fn contact_handler(
     State(state): State<AppState>,
-    Json(input): Json<ContactFormInput>,
+    Extension(request_id): Extension<RequestId>,
+    ClientIp(client_ip): ClientIp,
+    Json(mut input): Json<ContactFormInput>,
 ) -> AppResult<Json<Value>> {
     tracing::info!("Received contact form submission");
 
-    let form = ContactForm::try_from(input)?;
+    let now = chrono::Utc::now();
+
+    if let Some(signal) = guard::detect_bot(
+        &state.signer,
+        &state.config.form_guard,
+        &input.website,
+        input.form_token.as_deref(),
+        now.timestamp_millis(),
+    ) {
+        // Bots get the same answer as humans so they don't learn to adapt
+        tracing::warn!(
+            reason = signal.as_str(),
+            "Dropping automated contact submission"
+        );
+        state.metrics.incr(
+            "contact_bot_submissions_total",
+            &[("reason", signal.as_str())],
+        );
+        state.pow.penalize(client_ip, now.timestamp());
+        return Ok(Json(submitted()));
+    }
+
+    if let Some(captcha) = &state.captcha {
+        let token = input
+            .captcha_token
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| AppError::Validation("captcha_token: is required".to_string()))?;
+        if !captcha.verify(token, client_ip).await? {
+            state.metrics.incr(
+                "contact_captcha_rejected_total",
+                &[("provider", captcha.name())],
+            );
+            return Err(AppError::Validation(
+                "captcha_token: verification failed".to_string(),
+            ));
+        }
+    }
+
+    // The form is checked first so a typo doesn't burn the solved challenge
+    let pow_challenge = input.pow_challenge.take();
+    let pow_solution = input.pow_solution.take();
+    let form = ContactForm::parse(input, &state.email_policy)?;
+
+    if (state.pow.config().required || pow_challenge.is_some())
+        && let Err(e) = state.pow.verify(
+            &state.signer,
+            client_ip,
+            pow_challenge.as_deref(),
+            pow_solution.as_deref(),
+            now.timestamp(),
+        )
+    {
+        state
+            .metrics
+            .incr("contact_pow_rejected_total", &[("reason", e.as_str())]);
+        state.pow.penalize(client_ip, now.timestamp());
+        return Err(AppError::Validation(format!("pow_solution: {e}")));
+    }
+
+    let mut event = form.into_event(EventMetadata::new(request_id.0, client_ip));
+    let NotificationEvent::ContactSubmitted(contact) = &mut event;
+
+    let verdict = state.spam.score(contact);
+    if state.spam.is_spam(&verdict) {
+        let quarantine_id = state.outbox.quarantine(&event, &verdict).await?;
+        tracing::warn!(
+            quarantine_id,
+            score = verdict.score,
+            rules = ?verdict.signals.iter().map(|s| s.rule.as_str()).collect::<Vec<_>>(),
+            "Contact submission quarantined as spam"
+        );
+        for signal in &verdict.signals {
+            state.metrics.incr(
+                "contact_spam_quarantined_total",
+                &[("rule", signal.rule.as_str())],
+            );
+        }
+        // Spammers aren't told, so they don't tune their message
+        return Ok(Json(submitted()));
+    }
+    let email = contact.email.clone();
+    let request_id = contact.metadata.request_id.clone();
+    let original = state.duplicates.reserve(
+        &email,
+        &contact.message,
+        &request_id,
+        chrono::Utc::now().timestamp(),
+    );
+    if let Some(original) = &original {
+        let action = state.duplicates.config().action;
+        tracing::info!(
+            duplicate_of = %original,
+            action = action.as_str(),
+            "Duplicate contact submission"
+        );
+        state
+            .metrics
+            .incr("contact_duplicates_total", &[("action", action.as_str())]);
+        if action == DuplicateAction::Suppress {
+            return Ok(Json(submitted()));
+        }
+        contact.duplicate_of = Some(original.clone());
+    }
+
+    // The reservation only sticks once the submission is safely queued
+    let outbox_id = match state.outbox.enqueue(&event).await {
+        Ok(outbox_id) => outbox_id,
+        Err(e) => {
+            if original.is_none() {
+                state.duplicates.release(&email, &request_id);
+            }
+            return Err(e);
+        }
+    };
+    if original.is_none() {
+        state.duplicates.confirm(&email, &request_id);
+    }
 
-    let message = format_contact_message(&form);
-    state.notifier.notify(message).await?;
+    tracing::info!(outbox_id, "Contact form stored for delivery");
+    Ok(Json(submitted()))
+}
 
-    tracing::info!("Contact form processed successfully");
-    Ok(Json(json!({
+fn submitted() -> Value {
+    json!({
         "success": true,
         "message": "Contact form submitted successfully"
-    })))
+    })
 }
 
-fn format_contact_message(form: &ContactForm) -> String {
-    format!(
-        "**Yo brozer, nouvelle demande de contact!**\n\
-        👤 Nom: {}\n\
-        👤 Prénom: {}\n\
-        📞 Téléphone: {}\n\
-        📧 **Email:** {}\n\
-        📝 **Message:**\n{}",
-        form.last_name, form.first_name, form.number, form.email, form.message
-    )
+/// Issues a proof-of-work challenge, harder for IPs behaving abusively.
+#[tracing::instrument(skip(state))]
+pub(super) async fn challenge_handler(
+    State(state): State<AppState>,
+    ClientIp(client_ip): ClientIp,
+) -> Json<Challenge> {
+    let challenge = state
+        .pow
+        .issue(&state.signer, client_ip, chrono::Utc::now().timestamp());
+    tracing::debug!(
+        difficulty = challenge.difficulty,
+        "Issued contact challenge"
+    );
+    Json(challenge)
+}
+
+#[derive(Debug, Serialize)]
+pub struct FormTokenResponse {
+    pub token: String,
+    pub expires_at: String,
+}
+
+/// Issues the token the frontend sends back with the form, proving when it
+/// was rendered.
+#[tracing::instrument(skip(state))]
+pub(super) async fn form_token_handler(State(state): State<AppState>) -> Json<FormTokenResponse> {
+    let (token, expires_at) = guard::issue_form_token(
+        &state.signer,
+        &state.config.form_guard,
+        chrono::Utc::now().timestamp_millis(),
+    );
+
+    Json(FormTokenResponse {
+        token,
+        expires_at: chrono::DateTime::from_timestamp(expires_at, 0)
+            .unwrap_or_default()
+            .to_rfc3339(),
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TemplatePreviewInput {
+    pub channel: Option<TemplateChannel>,
+    pub locale: Option<String>,
+    /// Unsaved template source, rendered instead of a configured one.
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplatePreviewResponse {
+    pub rendered: String,
+}
+
+const DEFAULT_QUARANTINE_PAGE: u32 = 50;
+const MAX_QUARANTINE_PAGE: u32 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct QuarantineQuery {
+    pub limit: Option<u32>,
+    /// ID of the last entry of the previous page.
+    pub before: Option<i64>,
+}
+
+/// Submissions held back as spam, most recent first, a page at a time.
+#[tracing::instrument(skip(state))]
+pub(super) async fn quarantine_handler(
+    _admin: AdminAuth,
+    State(state): State<AppState>,
+    Query(query): Query<QuarantineQuery>,
+) -> AppResult<Json<Vec<QuarantinedEntry>>> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_QUARANTINE_PAGE)
+        .clamp(1, MAX_QUARANTINE_PAGE);
+    Ok(Json(state.outbox.quarantined(limit, query.before).await?))
+}
+
+/// Sends a quarantined submission on to the notifiers, teaching the
+/// classifier it wasn't spam.
+#[tracing::instrument(skip(state))]
+pub(super) async fn release_quarantined_handler(
+    _admin: AdminAuth,
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Value>> {
+    review_quarantined(&state, id, false).await?;
+    Ok(Json(json!({ "released": true })))
+}
+
+/// Drops a quarantined submission, teaching the classifier it was spam.
+#[tracing::instrument(skip(state))]
+pub(super) async fn discard_quarantined_handler(
+    _admin: AdminAuth,
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Value>> {
+    review_quarantined(&state, id, true).await?;
+    Ok(Json(json!({ "discarded": true })))
+}
+
+async fn review_quarantined(state: &AppState, id: i64, spam: bool) -> AppResult<()> {
+    let NotificationEvent::ContactSubmitted(contact) = state
+        .outbox
+        .take_quarantined(id, !spam)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Quarantined submission {}", id)))?;
+    tracing::info!(
+        request_id = %contact.metadata.request_id,
+        spam,
+        "Quarantined submission reviewed"
+    );
+    state.spam.train(&spam::training_text(&contact), spam).await
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct SpamTrainingInput {
+    pub message: String,
+    pub spam: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct SpamTrainingResponse {
+    pub spam_messages: u32,
+    pub ham_messages: u32,
+}
+
+/// Teaches the classifier from an example, e.g. spam that got notified.
+#[tracing::instrument(skip(state, input))]
+pub(super) async fn train_spam_handler(
+    _admin: AdminAuth,
+    State(state): State<AppState>,
+    Json(input): Json<SpamTrainingInput>,
+) -> AppResult<Json<SpamTrainingResponse>> {
+    if input.message.trim().is_empty() {
+        return Err(AppError::Validation("message: is required".to_string()));
+    }
+    state.spam.train(&input.message, input.spam).await?;
+
+    let (spam_messages, ham_messages) = state.spam.trained();
+    Ok(Json(SpamTrainingResponse {
+        spam_messages,
+        ham_messages,
+    }))
+}
+
+/// Renders a notification template against sample data.
+#[tracing::instrument(skip(state, input))]
+pub(super) async fn preview_template_handler(
+    _admin: AdminAuth,
+    State(state): State<AppState>,
+    Json(input): Json<TemplatePreviewInput>,
+) -> AppResult<Json<TemplatePreviewResponse>> {
+    let rendered = match (input.template, input.channel) {
+        (Some(source), _) => state.templates.render_source(&source),
+        (None, Some(channel)) => state.templates.render_in(
+            input.locale.as_deref().unwrap_or(state.templates.locale()),
+            channel,
+            &templates::sample_event(),
+        ),
+        (None, None) => Err("either channel or template is required".to_string()),
+    }
+    .map_err(|e| AppError::Validation(format!("template: {e}")))?;
+
+    Ok(Json(TemplatePreviewResponse { rendered }))
+}
+
+impl ContactForm {
+    fn into_event(self, metadata: EventMetadata) -> NotificationEvent {
+        NotificationEvent::ContactSubmitted(ContactSubmitted {
+            metadata,
+            category: self.category,
+            first_name: self.first_name.0,
+            last_name: self.last_name.0,
+            phone: self.number.0,
+            email: self.email.0,
+            message: self.message.0,
+            duplicate_of: None,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn policy() -> EmailPolicy {
+        EmailPolicy::new(None).unwrap()
+    }
+
     fn valid_input() -> ContactFormInput {
         ContactFormInput {
             first_name: "John".to_string(),
@@ -164,21 +482,30 @@ mod tests {
             number: "+1234567890".to_string(),
             email: "john@example.com".to_string(),
             message: "Test message".to_string(),
+            category: ContactCategory::default(),
+            website: String::new(),
+            form_token: None,
+            pow_challenge: None,
+            pow_solution: None,
+            captcha_token: None,
         }
     }
 
     #[test]
-    fn test_format_contact_message() {
-        let form = ContactForm::try_from(valid_input()).unwrap();
-        let message = format_contact_message(&form);
-        assert!(message.contains("John"));
-        assert!(message.contains("Doe"));
-        assert!(message.contains("john@example.com"));
+    fn test_into_event() {
+        let form = ContactForm::parse(valid_input(), &policy()).unwrap();
+        let NotificationEvent::ContactSubmitted(contact) =
+            form.into_event(EventMetadata::new("req-1".to_string(), None));
+        assert_eq!(contact.metadata.request_id, "req-1");
+        assert_eq!(contact.first_name, "John");
+        assert_eq!(contact.last_name, "Doe");
+        assert_eq!(contact.email, "john@example.com");
+        assert_eq!(contact.category, ContactCategory::Other);
     }
 
     #[test]
     fn test_valid_form() {
-        assert!(ContactForm::try_from(valid_input()).is_ok());
+        assert!(ContactForm::parse(valid_input(), &policy()).is_ok());
     }
 
     #[test]
@@ -187,7 +514,30 @@ mod tests {
             email: "invalid-email".to_string(),
             ..valid_input()
         };
-        assert!(ContactForm::try_from(input).is_err());
+        assert!(ContactForm::parse(input, &policy()).is_err());
+    }
+
+    #[test]
+    fn test_email_errors_are_reported_distinctly() {
+        let error = |email: &str| {
+            let input = ContactFormInput {
+                email: email.to_string(),
+                ..valid_input()
+            };
+            match ContactForm::parse(input, &policy()) {
+                Err(AppError::Validation(msg)) => msg,
+                _ => panic!("{email} should be rejected"),
+            }
+        };
+
+        assert_eq!(
+            error("john@yopmail.com"),
+            "email: disposable email addresses are not accepted"
+        );
+        assert_eq!(
+            error("john..doe@example.com"),
+            "email: local part can't start or end with a dot or contain consecutive dots"
+        );
     }
 
     #[test]
@@ -196,7 +546,7 @@ mod tests {
             message: "a".repeat(1001),
             ..valid_input()
         };
-        assert!(ContactForm::try_from(input).is_err());
+        assert!(ContactForm::parse(input, &policy()).is_err());
     }
 
     #[test]
@@ -205,6 +555,6 @@ mod tests {
             first_name: "".to_string(),
             ..valid_input()
         };
-        assert!(ContactForm::try_from(input).is_err());
+        assert!(ContactForm::parse(input, &policy()).is_err());
     }
 }
@@ -1,6 +1,20 @@
+pub mod captcha;
+pub mod dedup;
+pub mod email;
+pub mod guard;
 mod handler;
+pub mod outbox;
+pub mod pow;
 mod routes;
 pub mod service;
+pub mod spam;
 
+pub use captcha::{CaptchaConfig, CaptchaVerifier};
+pub use dedup::{DuplicateAction, DuplicateConfig, DuplicateDetector};
+pub use email::EmailPolicy;
+pub use guard::FormGuardConfig;
+pub use outbox::{Outbox, OutboxConfig};
+pub use pow::{PowConfig, ProofOfWork};
 pub use routes::contact_routes as routes;
-pub use service::{DiscordNotifier, Notification};
+pub use service::{DiscordNotifier, Notification, NotificationEvent};
+pub use spam::{SpamConfig, SpamFilter};
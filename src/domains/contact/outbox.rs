@@ -0,0 +1,669 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Serialize;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::Notify;
+
+use super::service::{DeliveryReport, Notification, NotificationEvent};
+use super::spam::SpamVerdict;
+use crate::common::errors::{AppError, AppResult};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS contact_outbox (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    request_id TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    delivered_channels TEXT NOT NULL DEFAULT '[]',
+    status TEXT NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at INTEGER NOT NULL,
+    last_error TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS contact_outbox_due ON contact_outbox (status, next_attempt_at);
+CREATE TABLE IF NOT EXISTS contact_quarantine (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    request_id TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    verdict TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+";
+
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(3600);
+const BATCH_SIZE: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxStatus {
+    Pending,
+    Delivered,
+    /// Gave up after `max_attempts`, kept for manual inspection.
+    DeadLetter,
+}
+
+impl OutboxStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            OutboxStatus::Pending => "pending",
+            OutboxStatus::Delivered => "delivered",
+            OutboxStatus::DeadLetter => "dead_letter",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OutboxEntry {
+    pub id: i64,
+    pub attempts: u32,
+    pub event: NotificationEvent,
+    /// Channels that already got the event, retries skip them.
+    pub delivered_channels: Vec<String>,
+}
+
+/// Submission held back as spam until an admin releases or discards it.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuarantinedEntry {
+    pub id: i64,
+    pub verdict: SpamVerdict,
+    pub event: NotificationEvent,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct OutboxConfig {
+    pub max_attempts: u32,
+    pub retry_base_delay: Duration,
+    pub poll_interval: Duration,
+}
+
+/// SQLite backed queue of contact submissions awaiting notification.
+///
+/// Submissions are stored before any delivery attempt so a notifier outage
+/// delays a lead instead of losing it.
+pub struct Outbox {
+    conn: Arc<Mutex<Connection>>,
+    config: OutboxConfig,
+    wake: Notify,
+}
+
+impl Outbox {
+    pub fn open(path: impl AsRef<Path>, config: OutboxConfig) -> anyhow::Result<Self> {
+        Self::from_connection(Connection::open(path)?, config)
+    }
+
+    pub fn in_memory(config: OutboxConfig) -> anyhow::Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?, config)
+    }
+
+    fn from_connection(conn: Connection, config: OutboxConfig) -> anyhow::Result<Self> {
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            config,
+            wake: Notify::new(),
+        })
+    }
+
+    /// Runs a query on the blocking pool, SQLite calls must not stall the runtime.
+    async fn with_conn<T, F>(&self, f: F) -> AppResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || f(&conn.lock().expect("outbox lock poisoned")))
+            .await
+            .map_err(|e| AppError::Database(format!("outbox task failed: {}", e)))?
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// Durably stores a submission and wakes the worker up.
+    pub async fn enqueue(&self, event: &NotificationEvent) -> AppResult<i64> {
+        let request_id = event.request_id().to_string();
+        let payload = serde_json::to_string(event)
+            .map_err(|e| AppError::Database(format!("failed to serialize payload: {}", e)))?;
+        let now = chrono::Utc::now().timestamp();
+
+        let id = self
+            .with_conn(move |conn| {
+                conn.execute(
+                    "INSERT INTO contact_outbox
+                        (request_id, payload, next_attempt_at, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?3, ?3)",
+                    params![request_id, payload, now],
+                )?;
+                Ok(conn.last_insert_rowid())
+            })
+            .await?;
+
+        self.wake.notify_one();
+        Ok(id)
+    }
+
+    /// Stores a submission that won't be notified unless released.
+    pub async fn quarantine(
+        &self,
+        event: &NotificationEvent,
+        verdict: &SpamVerdict,
+    ) -> AppResult<i64> {
+        let request_id = event.request_id().to_string();
+        let payload = serde_json::to_string(event)
+            .map_err(|e| AppError::Database(format!("failed to serialize payload: {}", e)))?;
+        let verdict = serde_json::to_string(verdict)
+            .map_err(|e| AppError::Database(format!("failed to serialize verdict: {}", e)))?;
+        let now = chrono::Utc::now().timestamp();
+
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO contact_quarantine (request_id, payload, verdict, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![request_id, payload, verdict, now],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+        .await
+    }
+
+    /// Up to `limit` quarantined submissions, most recent first. `before`
+    /// is the last ID of the previous page.
+    pub async fn quarantined(
+        &self,
+        limit: u32,
+        before: Option<i64>,
+    ) -> AppResult<Vec<QuarantinedEntry>> {
+        let rows = self
+            .with_conn(move |conn| {
+                let mut statement = conn.prepare(
+                    "SELECT id, payload, verdict, created_at FROM contact_quarantine
+                     WHERE ?1 IS NULL OR id < ?1
+                     ORDER BY id DESC LIMIT ?2",
+                )?;
+                statement
+                    .query_map(params![before, limit], |row| {
+                        Ok((
+                            row.get::<_, i64>(0)?,
+                            row.get::<_, String>(1)?,
+                            row.get::<_, String>(2)?,
+                            row.get::<_, i64>(3)?,
+                        ))
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            })
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(id, payload, verdict, created_at)| {
+                match (
+                    serde_json::from_str(&payload),
+                    serde_json::from_str(&verdict),
+                ) {
+                    (Ok(event), Ok(verdict)) => Some(QuarantinedEntry {
+                        id,
+                        verdict,
+                        event,
+                        created_at,
+                    }),
+                    (Err(e), _) | (_, Err(e)) => {
+                        tracing::error!(id, "Skipping unreadable quarantine entry: {}", e);
+                        None
+                    }
+                }
+            })
+            .collect())
+    }
+
+    /// Removes a submission from the quarantine, returning it. With
+    /// `release`, it's queued for notification in the same transaction.
+    pub async fn take_quarantined(
+        &self,
+        id: i64,
+        release: bool,
+    ) -> AppResult<Option<NotificationEvent>> {
+        let now = chrono::Utc::now().timestamp();
+        let payload = self
+            .with_conn(move |conn| {
+                let tx = conn.unchecked_transaction()?;
+                let row = tx
+                    .query_row(
+                        "SELECT request_id, payload FROM contact_quarantine WHERE id = ?1",
+                        params![id],
+                        |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+                    )
+                    .optional()?;
+                let Some((request_id, payload)) = row else {
+                    return Ok(None);
+                };
+
+                tx.execute("DELETE FROM contact_quarantine WHERE id = ?1", params![id])?;
+                if release {
+                    tx.execute(
+                        "INSERT INTO contact_outbox
+                            (request_id, payload, next_attempt_at, created_at, updated_at)
+                         VALUES (?1, ?2, ?3, ?3, ?3)",
+                        params![request_id, payload, now],
+                    )?;
+                }
+                tx.commit()?;
+                Ok(Some(payload))
+            })
+            .await?;
+
+        if release && payload.is_some() {
+            self.wake.notify_one();
+        }
+        payload
+            .map(|payload| serde_json::from_str(&payload))
+            .transpose()
+            .map_err(|e| AppError::Database(format!("unreadable quarantine entry: {}", e)))
+    }
+
+    /// Pending entries whose next attempt is due at `now`.
+    pub async fn due(&self, now: i64) -> AppResult<Vec<OutboxEntry>> {
+        let rows = self
+            .with_conn(move |conn| {
+                let mut statement = conn.prepare(
+                    "SELECT id, attempts, payload, delivered_channels FROM contact_outbox
+                     WHERE status = 'pending' AND next_attempt_at <= ?1
+                     ORDER BY next_attempt_at LIMIT ?2",
+                )?;
+                statement
+                    .query_map(params![now, BATCH_SIZE as i64], |row| {
+                        Ok((
+                            row.get::<_, i64>(0)?,
+                            row.get::<_, u32>(1)?,
+                            row.get::<_, String>(2)?,
+                            row.get::<_, String>(3)?,
+                        ))
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            })
+            .await?;
+
+        let mut entries = Vec::with_capacity(rows.len());
+        for (id, attempts, payload, delivered_channels) in rows {
+            let parsed = serde_json::from_str(&payload)
+                .and_then(|event| Ok((event, serde_json::from_str(&delivered_channels)?)));
+            match parsed {
+                Ok((event, delivered_channels)) => entries.push(OutboxEntry {
+                    id,
+                    attempts,
+                    event,
+                    delivered_channels,
+                }),
+                Err(e) => {
+                    // Retrying can't fix a payload, park it so it isn't read on every poll
+                    tracing::error!(id, "Dead-lettering unreadable outbox entry: {}", e);
+                    self.mark_unreadable(id, format!("unreadable payload: {}", e))
+                        .await?;
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    async fn mark_unreadable(&self, id: i64, error: String) -> AppResult<()> {
+        let now = chrono::Utc::now().timestamp();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "UPDATE contact_outbox
+                 SET status = ?2, last_error = ?3, updated_at = ?4
+                 WHERE id = ?1",
+                params![id, OutboxStatus::DeadLetter.as_str(), error, now],
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_delivered(&self, id: i64) -> AppResult<()> {
+        let now = chrono::Utc::now().timestamp();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "UPDATE contact_outbox
+                 SET status = 'delivered', attempts = attempts + 1, last_error = NULL, updated_at = ?2
+                 WHERE id = ?1",
+                params![id, now],
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Records a failed attempt, along with the channels that got the event
+    /// anyway, and either reschedules the entry with exponential backoff or
+    /// moves it to the dead letter state.
+    pub async fn mark_failed(
+        &self,
+        entry: &OutboxEntry,
+        report: &DeliveryReport,
+    ) -> AppResult<OutboxStatus> {
+        let attempts = entry.attempts + 1;
+        let now = chrono::Utc::now().timestamp();
+        let status = if attempts >= self.config.max_attempts {
+            OutboxStatus::DeadLetter
+        } else {
+            OutboxStatus::Pending
+        };
+        let next_attempt_at =
+            now + retry_delay(self.config.retry_base_delay, attempts).as_secs() as i64;
+        let id = entry.id;
+        let error = report.summary();
+        let delivered_channels = serde_json::to_string(&report.delivered_channels())
+            .map_err(|e| AppError::Database(format!("failed to serialize channels: {}", e)))?;
+
+        self.with_conn(move |conn| {
+            conn.execute(
+                "UPDATE contact_outbox
+                 SET status = ?2, attempts = ?3, next_attempt_at = ?4, last_error = ?5,
+                     delivered_channels = ?6, updated_at = ?7
+                 WHERE id = ?1",
+                params![
+                    id,
+                    status.as_str(),
+                    attempts,
+                    next_attempt_at,
+                    error,
+                    delivered_channels,
+                    now
+                ],
+            )
+        })
+        .await?;
+
+        Ok(status)
+    }
+
+    pub async fn status(&self, id: i64) -> AppResult<Option<OutboxStatus>> {
+        let status = self
+            .with_conn(move |conn| {
+                conn.query_row(
+                    "SELECT status FROM contact_outbox WHERE id = ?1",
+                    params![id],
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()
+            })
+            .await?;
+
+        Ok(status.map(|s| match s.as_str() {
+            "delivered" => OutboxStatus::Delivered,
+            "dead_letter" => OutboxStatus::DeadLetter,
+            _ => OutboxStatus::Pending,
+        }))
+    }
+
+    /// Attempts every due entry once.
+    pub async fn process_due(&self, notifier: &dyn Notification) -> AppResult<()> {
+        for entry in self.due(chrono::Utc::now().timestamp()).await? {
+            let report = notifier
+                .deliver(&entry.event, &entry.delivered_channels)
+                .await;
+            let request_id = entry.event.request_id();
+
+            if report.delivered {
+                self.mark_delivered(entry.id).await?;
+                tracing::info!(request_id = %request_id, "Outbox entry {} delivered", entry.id);
+                continue;
+            }
+
+            match self.mark_failed(&entry, &report).await? {
+                OutboxStatus::DeadLetter => tracing::error!(
+                    request_id = %request_id,
+                    "Outbox entry {} dead-lettered after {} attempts: {}",
+                    entry.id,
+                    entry.attempts + 1,
+                    report.summary()
+                ),
+                _ => tracing::warn!(
+                    request_id = %request_id,
+                    "Outbox entry {} failed, will retry: {}",
+                    entry.id,
+                    report.summary()
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Delivers queued submissions in the background, right after they are
+    /// enqueued and then every `poll_interval` for retries.
+    pub fn spawn_worker(self: Arc<Self>, notifier: Arc<dyn Notification>) {
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = self.process_due(notifier.as_ref()).await {
+                    tracing::error!("Outbox worker failed: {}", e);
+                }
+                tokio::select! {
+                    _ = self.wake.notified() => {}
+                    _ = tokio::time::sleep(self.config.poll_interval) => {}
+                }
+            }
+        });
+    }
+}
+
+/// Exponential backoff, `base * 2^(attempts - 1)` capped at one hour.
+fn retry_delay(base: Duration, attempts: u32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+        .min(MAX_RETRY_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    struct FlakyNotifier {
+        failures: AtomicU32,
+    }
+
+    #[async_trait]
+    impl Notification for FlakyNotifier {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn notify(&self, _event: &NotificationEvent) -> AppResult<()> {
+            if self.failures.load(Ordering::SeqCst) > 0 {
+                self.failures.fetch_sub(1, Ordering::SeqCst);
+                return Err(AppError::DiscordApi("down".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    fn outbox(max_attempts: u32) -> Outbox {
+        Outbox::in_memory(OutboxConfig {
+            max_attempts,
+            retry_base_delay: Duration::ZERO,
+            poll_interval: Duration::from_secs(1),
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_is_retried() {
+        let outbox = outbox(5);
+        let notifier = FlakyNotifier {
+            failures: AtomicU32::new(1),
+        };
+        let id = outbox.enqueue(&NotificationEvent::sample()).await.unwrap();
+
+        outbox.process_due(&notifier).await.unwrap();
+        assert_eq!(
+            outbox.status(id).await.unwrap(),
+            Some(OutboxStatus::Pending)
+        );
+
+        outbox.process_due(&notifier).await.unwrap();
+        assert_eq!(
+            outbox.status(id).await.unwrap(),
+            Some(OutboxStatus::Delivered)
+        );
+        assert!(outbox.due(i64::MAX).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retry_skips_channels_already_notified() {
+        use super::super::service::{DeliveryPolicy, FanoutNotifier};
+
+        struct CountingNotifier {
+            calls: AtomicU32,
+        }
+
+        #[async_trait]
+        impl Notification for CountingNotifier {
+            fn name(&self) -> &str {
+                "counting"
+            }
+
+            async fn notify(&self, _event: &NotificationEvent) -> AppResult<()> {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        }
+
+        let outbox = outbox(5);
+        let counting = Arc::new(CountingNotifier {
+            calls: AtomicU32::new(0),
+        });
+        let flaky = Arc::new(FlakyNotifier {
+            failures: AtomicU32::new(1),
+        });
+        let notifier =
+            FanoutNotifier::new(vec![counting.clone(), flaky.clone()], DeliveryPolicy::All);
+        let id = outbox.enqueue(&NotificationEvent::sample()).await.unwrap();
+
+        outbox.process_due(&notifier).await.unwrap();
+        let entries = outbox.due(i64::MAX).await.unwrap();
+        assert_eq!(entries[0].delivered_channels, ["counting"]);
+
+        outbox.process_due(&notifier).await.unwrap();
+        assert_eq!(
+            outbox.status(id).await.unwrap(),
+            Some(OutboxStatus::Delivered)
+        );
+        assert_eq!(counting.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_after_max_attempts() {
+        let outbox = outbox(2);
+        let notifier = FlakyNotifier {
+            failures: AtomicU32::new(10),
+        };
+        let id = outbox.enqueue(&NotificationEvent::sample()).await.unwrap();
+
+        outbox.process_due(&notifier).await.unwrap();
+        outbox.process_due(&notifier).await.unwrap();
+
+        assert_eq!(
+            outbox.status(id).await.unwrap(),
+            Some(OutboxStatus::DeadLetter)
+        );
+        assert!(outbox.due(i64::MAX).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_payload_round_trips() {
+        let outbox = outbox(1);
+        outbox.enqueue(&NotificationEvent::sample()).await.unwrap();
+
+        let entries = outbox.due(i64::MAX).await.unwrap();
+        assert_eq!(entries[0].event.request_id(), "req-1");
+        let NotificationEvent::ContactSubmitted(contact) = &entries[0].event;
+        assert_eq!(contact.email, "john@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_entry_is_dead_lettered() {
+        let outbox = outbox(5);
+        let id = outbox
+            .with_conn(|conn| {
+                conn.execute(
+                    "INSERT INTO contact_outbox
+                        (request_id, payload, next_attempt_at, created_at, updated_at)
+                     VALUES ('req-1', 'not json', 0, 0, 0)",
+                    [],
+                )?;
+                Ok(conn.last_insert_rowid())
+            })
+            .await
+            .unwrap();
+
+        assert!(outbox.due(i64::MAX).await.unwrap().is_empty());
+        assert_eq!(
+            outbox.status(id).await.unwrap(),
+            Some(OutboxStatus::DeadLetter)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quarantine_release_and_discard() {
+        let outbox = outbox(1);
+        let verdict = SpamVerdict {
+            score: 6.0,
+            signals: vec![],
+        };
+        let first = outbox
+            .quarantine(&NotificationEvent::sample(), &verdict)
+            .await
+            .unwrap();
+        let second = outbox
+            .quarantine(&NotificationEvent::sample(), &verdict)
+            .await
+            .unwrap();
+
+        let quarantined = outbox.quarantined(10, None).await.unwrap();
+        assert_eq!(quarantined.len(), 2);
+        assert_eq!(quarantined[0].verdict, verdict);
+
+        let page = outbox.quarantined(1, None).await.unwrap();
+        assert_eq!(page[0].id, second);
+        let page = outbox.quarantined(1, Some(page[0].id)).await.unwrap();
+        assert_eq!(page[0].id, first);
+        assert!(outbox.quarantined(1, Some(first)).await.unwrap().is_empty());
+        assert!(outbox.due(i64::MAX).await.unwrap().is_empty());
+
+        assert!(
+            outbox
+                .take_quarantined(first, true)
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            outbox
+                .take_quarantined(second, false)
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            outbox
+                .take_quarantined(second, false)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(outbox.quarantined(10, None).await.unwrap().is_empty());
+        assert_eq!(outbox.due(i64::MAX).await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_retry_delay_is_capped() {
+        let base = Duration::from_secs(30);
+        assert_eq!(retry_delay(base, 1), Duration::from_secs(30));
+        assert_eq!(retry_delay(base, 3), Duration::from_secs(120));
+        assert_eq!(retry_delay(base, 30), MAX_RETRY_DELAY);
+    }
+}
@@ -0,0 +1,335 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, VecDeque},
+    net::IpAddr,
+    sync::Mutex,
+    time::Duration,
+};
+
+use crate::common::signing::Signer;
+
+pub const POW_AUDIENCE: &str = "contact-pow";
+
+/// Above this many tracked IPs, idle ones are forgotten.
+const MAX_TRACKED_IPS: usize = 10_000;
+
+/// Highest accepted difficulty, each bit doubles the client's work and 32
+/// already takes minutes in a browser.
+pub const MAX_DIFFICULTY: u32 = 32;
+
+#[derive(Debug, Clone)]
+pub struct PowConfig {
+    /// Rejects submissions without a solved challenge. Off while the frontend
+    /// doesn't solve challenges yet.
+    pub required: bool,
+    /// Leading zero bits required from the solution hash.
+    pub base_difficulty: u32,
+    pub max_difficulty: u32,
+    pub ttl: Duration,
+    /// Challenges and bot signals from an IP are remembered this long.
+    pub abuse_window: Duration,
+    /// Events tolerated per window before each new one adds a bit of difficulty.
+    pub abuse_threshold: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ChallengeClaims {
+    nonce: String,
+    difficulty: u32,
+    ip: Option<IpAddr>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Challenge {
+    pub challenge: String,
+    pub difficulty: u32,
+    pub expires_at: i64,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum PowError {
+    #[error("proof of work is required")]
+    Missing,
+
+    #[error("challenge is invalid or expired")]
+    InvalidChallenge,
+
+    #[error("solution doesn't meet the challenge difficulty")]
+    WrongSolution,
+
+    #[error("challenge was already used")]
+    Replayed,
+}
+
+impl PowError {
+    /// Metric label.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PowError::Missing => "missing",
+            PowError::InvalidChallenge => "invalid_challenge",
+            PowError::WrongSolution => "wrong_solution",
+            PowError::Replayed => "replayed",
+        }
+    }
+}
+
+/// Hashcash-style challenges, a self-hosted alternative to third-party CAPTCHAs.
+///
+/// The client looks for a `solution` such that `SHA-256("{challenge}:{solution}")`
+/// starts with `difficulty` zero bits. Challenges are stateless signed tokens,
+/// only the consumed ones are remembered until they expire to prevent replay.
+/// IPs requesting many challenges or caught by the bot checks get harder ones.
+pub struct ProofOfWork {
+    config: PowConfig,
+    used: Mutex<HashMap<String, i64>>,
+    activity: Mutex<HashMap<IpAddr, VecDeque<i64>>>,
+}
+
+impl ProofOfWork {
+    pub fn new(config: PowConfig) -> Self {
+        Self {
+            config,
+            used: Mutex::new(HashMap::new()),
+            activity: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &PowConfig {
+        &self.config
+    }
+
+    pub fn difficulty_for(&self, ip: Option<IpAddr>, now: i64) -> u32 {
+        let Some(ip) = ip else {
+            return self.config.base_difficulty;
+        };
+        let window_start = now - self.config.abuse_window.as_secs() as i64;
+        let recent = self
+            .activity
+            .lock()
+            .expect("pow activity lock poisoned")
+            .get(&ip)
+            .map(|events| events.iter().filter(|&&at| at > window_start).count() as u32)
+            .unwrap_or(0);
+
+        (self.config.base_difficulty + recent.saturating_sub(self.config.abuse_threshold))
+            .min(self.config.max_difficulty)
+    }
+
+    /// Records suspicious activity from `ip`, raising its next difficulties.
+    pub fn penalize(&self, ip: Option<IpAddr>, now: i64) {
+        let Some(ip) = ip else {
+            return;
+        };
+        let window_start = now - self.config.abuse_window.as_secs() as i64;
+        let mut activity = self.activity.lock().expect("pow activity lock poisoned");
+
+        // Past this many events the difficulty is already at its maximum
+        let max_events = (self.config.abuse_threshold
+            + self
+                .config
+                .max_difficulty
+                .saturating_sub(self.config.base_difficulty))
+        .max(1) as usize;
+
+        let events = activity.entry(ip).or_default();
+        while events.front().is_some_and(|&at| at <= window_start) || events.len() >= max_events {
+            events.pop_front();
+        }
+        events.push_back(now);
+
+        if activity.len() > MAX_TRACKED_IPS {
+            activity.retain(|_, events| events.back().is_some_and(|&at| at > window_start));
+        }
+    }
+
+    pub fn issue(&self, signer: &Signer, ip: Option<IpAddr>, now: i64) -> Challenge {
+        let difficulty = self.difficulty_for(ip, now);
+        // Asking for challenges counts too, so hammering the endpoint costs
+        self.penalize(ip, now);
+
+        let expires_at = now + self.config.ttl.as_secs() as i64;
+        let claims = ChallengeClaims {
+            nonce: hex::encode(rand::random::<[u8; 16]>()),
+            difficulty,
+            ip,
+        };
+
+        Challenge {
+            challenge: signer.sign(POW_AUDIENCE, &claims, expires_at),
+            difficulty,
+            expires_at,
+        }
+    }
+
+    /// Checks the solution and consumes the challenge.
+    pub fn verify(
+        &self,
+        signer: &Signer,
+        ip: Option<IpAddr>,
+        challenge: Option<&str>,
+        solution: Option<&str>,
+        now: i64,
+    ) -> Result<(), PowError> {
+        let (Some(challenge), Some(solution)) = (challenge, solution) else {
+            return Err(PowError::Missing);
+        };
+
+        let claims: ChallengeClaims = signer
+            .verify_at(POW_AUDIENCE, challenge, now)
+            .map_err(|_| PowError::InvalidChallenge)?;
+        if claims.ip.is_some() && claims.ip != ip {
+            return Err(PowError::InvalidChallenge);
+        }
+
+        if leading_zero_bits(&hash(challenge, solution)) < claims.difficulty {
+            return Err(PowError::WrongSolution);
+        }
+
+        let mut used = self.used.lock().expect("pow replay lock poisoned");
+        used.retain(|_, &mut expires_at| expires_at >= now);
+        if used.contains_key(&claims.nonce) {
+            return Err(PowError::Replayed);
+        }
+        used.insert(claims.nonce, now + self.config.ttl.as_secs() as i64);
+        Ok(())
+    }
+}
+
+fn hash(challenge: &str, solution: &str) -> [u8; 32] {
+    Sha256::digest(format!("{}:{}", challenge, solution).as_bytes()).into()
+}
+
+fn leading_zero_bits(hash: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in hash {
+        if *byte == 0 {
+            bits += 8;
+        } else {
+            return bits + byte.leading_zeros();
+        }
+    }
+    bits
+}
+
+/// Reference solver, doing what the frontend does.
+#[cfg(test)]
+fn solve(challenge: &str, difficulty: u32) -> String {
+    (0u64..)
+        .map(|counter| counter.to_string())
+        .find(|solution| leading_zero_bits(&hash(challenge, solution)) >= difficulty)
+        .expect("a solution exists for any reasonable difficulty")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pow() -> ProofOfWork {
+        ProofOfWork::new(PowConfig {
+            required: true,
+            base_difficulty: 8,
+            max_difficulty: 12,
+            ttl: Duration::from_secs(600),
+            abuse_window: Duration::from_secs(600),
+            abuse_threshold: 2,
+        })
+    }
+
+    const IP: Option<IpAddr> = Some(IpAddr::V4(std::net::Ipv4Addr::new(203, 0, 113, 7)));
+
+    #[test]
+    fn test_leading_zero_bits() {
+        assert_eq!(leading_zero_bits(&[0, 0, 0x1f, 0xff]), 19);
+        assert_eq!(leading_zero_bits(&[0x80]), 0);
+        assert_eq!(leading_zero_bits(&[0, 0]), 16);
+    }
+
+    #[test]
+    fn test_solved_challenge_is_accepted_once() {
+        let (pow, signer) = (pow(), Signer::new("secret"));
+        let challenge = pow.issue(&signer, IP, 1000);
+        let solution = solve(&challenge.challenge, challenge.difficulty);
+
+        let verify = || {
+            pow.verify(
+                &signer,
+                IP,
+                Some(&challenge.challenge),
+                Some(&solution),
+                1010,
+            )
+        };
+
+        assert_eq!(verify(), Ok(()));
+        assert_eq!(verify(), Err(PowError::Replayed));
+    }
+
+    #[test]
+    fn test_rejected_solutions() {
+        let (pow, signer) = (pow(), Signer::new("secret"));
+        let challenge = pow.issue(&signer, IP, 1000);
+        let solution = solve(&challenge.challenge, challenge.difficulty);
+        let wrong = (0u64..)
+            .map(|c| c.to_string())
+            .find(|s| leading_zero_bits(&hash(&challenge.challenge, s)) == 0)
+            .unwrap();
+
+        assert_eq!(
+            pow.verify(&signer, IP, None, None, 1010),
+            Err(PowError::Missing)
+        );
+        assert_eq!(
+            pow.verify(&signer, IP, Some(&challenge.challenge), Some(&wrong), 1010),
+            Err(PowError::WrongSolution)
+        );
+        assert_eq!(
+            pow.verify(
+                &signer,
+                Some([198, 51, 100, 1].into()),
+                Some(&challenge.challenge),
+                Some(&solution),
+                1010
+            ),
+            Err(PowError::InvalidChallenge)
+        );
+        assert_eq!(
+            pow.verify(
+                &signer,
+                IP,
+                Some(&challenge.challenge),
+                Some(&solution),
+                1000 + 601
+            ),
+            Err(PowError::InvalidChallenge)
+        );
+    }
+
+    #[test]
+    fn test_difficulty_escalates_for_abusive_ips() {
+        let (pow, signer) = (pow(), Signer::new("secret"));
+
+        let difficulties = (0..8)
+            .map(|_| pow.issue(&signer, IP, 1000).difficulty)
+            .collect::<Vec<_>>();
+
+        assert_eq!(difficulties, vec![8, 8, 8, 9, 10, 11, 12, 12]);
+        assert_eq!(pow.difficulty_for(Some([198, 51, 100, 1].into()), 1000), 8);
+        // Forgiven once the window has passed
+        assert_eq!(pow.difficulty_for(IP, 1000 + 601), 8);
+    }
+
+    #[test]
+    fn test_activity_is_capped_per_ip() {
+        let (pow, signer) = (pow(), Signer::new("secret"));
+
+        for _ in 0..100 {
+            pow.issue(&signer, IP, 1000);
+        }
+
+        let activity = pow.activity.lock().unwrap();
+        assert_eq!(activity[&IP.unwrap()].len(), 6);
+        drop(activity);
+        assert_eq!(pow.difficulty_for(IP, 1000), 12);
+    }
+}
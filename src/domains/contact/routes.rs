@@ -1,7 +1,31 @@
-use axum::{Router, routing::post};
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
 
-use crate::{common::AppState, domains::contact::handler::contact_handler};
+use crate::{
+    common::AppState,
+    domains::contact::handler::{
+        challenge_handler, contact_handler, discard_quarantined_handler, form_token_handler,
+        preview_template_handler, quarantine_handler, release_quarantined_handler,
+        train_spam_handler,
+    },
+};
 
 pub fn contact_routes() -> Router<AppState> {
-    Router::new().route("/contact", post(contact_handler))
+    Router::new()
+        .route("/contact", post(contact_handler))
+        .route("/contact/token", get(form_token_handler))
+        .route("/contact/challenge", get(challenge_handler))
+        .route("/contact/templates/preview", post(preview_template_handler))
+        .route("/contact/quarantine", get(quarantine_handler))
+        .route(
+            "/contact/quarantine/{id}",
+            delete(discard_quarantined_handler),
+        )
+        .route(
+            "/contact/quarantine/{id}/release",
+            post(release_quarantined_handler),
+        )
+        .route("/contact/spam/train", post(train_spam_handler))
 }
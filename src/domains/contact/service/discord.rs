@@ -0,0 +1,296 @@
+use async_trait::async_trait;
+use futures_util::{StreamExt, stream};
+use reqwest::StatusCode;
+use std::{path::PathBuf, sync::Arc};
+
+use super::{
+    DiscordClient, DmChannelCache, Notification, NotificationEvent, NotificationTemplates, embed,
+};
+use crate::common::errors::{AppError, AppResult};
+
+/// Discord error code returned when a channel no longer exists.
+const UNKNOWN_CHANNEL: i64 = 10003;
+
+#[derive(Clone)]
+pub struct DiscordConfig {
+    pub bot_token: String,
+    pub user_ids: Vec<String>,
+    /// Overridable to point at a fake Discord in tests.
+    pub api_base_url: String,
+    /// JSON file keeping DM channel IDs across restarts, in memory only if unset.
+    pub dm_cache_path: Option<PathBuf>,
+    /// Maximum number of recipients notified at the same time.
+    pub max_concurrency: usize,
+}
+
+pub struct DiscordNotifier {
+    client: DiscordClient,
+    user_ids: Vec<String>,
+    dm_channels: DmChannelCache,
+    max_concurrency: usize,
+    templates: Arc<NotificationTemplates>,
+}
+
+impl DiscordNotifier {
+    pub fn new(
+        client: DiscordClient,
+        user_ids: Vec<String>,
+        dm_channels: DmChannelCache,
+        max_concurrency: usize,
+        templates: Arc<NotificationTemplates>,
+    ) -> Self {
+        Self {
+            client,
+            user_ids,
+            dm_channels,
+            max_concurrency: max_concurrency.max(1),
+            templates,
+        }
+    }
+
+    /// Cached DM channel of `user_id`, created on first use.
+    async fn dm_channel(&self, user_id: &str) -> AppResult<String> {
+        if let Some(channel_id) = self.dm_channels.get(user_id) {
+            return Ok(channel_id);
+        }
+
+        tracing::debug!("Creating DM channel for user {}", user_id);
+
+        let payload = serde_json::json!({ "recipient_id": user_id });
+        let dm_response = self
+            .client
+            .post_json(
+                "POST /users/@me/channels",
+                &self.client.url("/users/@me/channels"),
+                &payload,
+            )
+            .await
+            .map_err(|e| AppError::DiscordApi(format!("Failed to create DM channel: {}", e)))?
+            .error_for_status()
+            .map_err(|e| AppError::DiscordApi(format!("Discord API error: {}", e)))?;
+
+        let dm_channel: serde_json::Value = dm_response.json().await.map_err(|e| {
+            AppError::DiscordApi(format!("Failed to parse DM channel response: {}", e))
+        })?;
+
+        let channel_id = dm_channel["id"]
+            .as_str()
+            .ok_or_else(|| AppError::DiscordApi("Failed to get DM channel ID".to_string()))?;
+
+        self.dm_channels.insert(user_id, channel_id).await;
+        Ok(channel_id.to_string())
+    }
+
+    #[tracing::instrument(skip(self, event), fields(user_id = %user_id))]
+    async fn send_dm_to_user(&self, user_id: &str, event: &NotificationEvent) -> AppResult<()> {
+        // A cached channel may have been deleted since, in which case the
+        // entry is dropped and a fresh channel is opened once.
+        for attempt in 0..2 {
+            let channel_id = self.dm_channel(user_id).await?;
+
+            tracing::debug!("Sending message to channel {}", channel_id);
+
+            let route = format!("POST /channels/{}/messages", channel_id);
+            let message_url = self
+                .client
+                .url(&format!("/channels/{}/messages", channel_id));
+
+            let response = embed::post_embed(
+                |payload| {
+                    let (route, message_url) = (&route, &message_url);
+                    async move { self.client.post_json(route, message_url, &payload).await }
+                },
+                serde_json::json!({}),
+                &self.templates,
+                event,
+            )
+            .await?;
+
+            if response.status() == StatusCode::NOT_FOUND && attempt == 0 {
+                let body: serde_json::Value = response.json().await.unwrap_or_default();
+                if body["code"].as_i64() == Some(UNKNOWN_CHANNEL) {
+                    tracing::warn!("DM channel {} is gone, refreshing cache", channel_id);
+                    self.dm_channels.remove(user_id).await;
+                    continue;
+                }
+                return Err(AppError::DiscordApi(format!(
+                    "Discord API error: 404 Not Found: {}",
+                    body
+                )));
+            }
+
+            response
+                .error_for_status()
+                .map_err(|e| AppError::DiscordApi(format!("Discord API error: {}", e)))?;
+
+            tracing::info!("Successfully sent Discord notification to user {}", user_id);
+            return Ok(());
+        }
+
+        Err(AppError::DiscordApi(format!(
+            "DM channel for user {} could not be resolved",
+            user_id
+        )))
+    }
+}
+
+#[async_trait]
+impl Notification for DiscordNotifier {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    #[tracing::instrument(skip(self, event), fields(request_id = %event.request_id()))]
+    async fn notify(&self, event: &NotificationEvent) -> AppResult<()> {
+        // Owned IDs keep the stream future `Send` for `async_trait`
+        let results = stream::iter(self.user_ids.clone())
+            .map(|user_id| async move {
+                let result = self.send_dm_to_user(&user_id, event).await;
+                (user_id, result)
+            })
+            .buffer_unordered(self.max_concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut errors = Vec::new();
+        let mut success_count = 0;
+
+        for (user_id, result) in results {
+            match result {
+                Ok(()) => {
+                    success_count += 1;
+                    tracing::info!("Notified user {}", user_id);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to notify user {}: {}", user_id, e);
+                    errors.push(format!("User {}: {}", user_id, e));
+                }
+            }
+        }
+
+        if success_count == 0 {
+            Err(AppError::DiscordApi(format!(
+                "All notifications failed: {}",
+                errors.join("; ")
+            )))
+        } else if !errors.is_empty() {
+            tracing::warn!(
+                "Partial notification failure: {}/{} succeeded",
+                success_count,
+                self.user_ids.len()
+            );
+            Ok(())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::discord_client::tests::fake_discord;
+    use super::*;
+    use crate::common::metrics::Metrics;
+    use std::sync::{Arc, atomic::Ordering};
+
+    #[tokio::test]
+    async fn test_dm_survives_rate_limit() {
+        let (base_url, calls) = fake_discord(vec![
+            (200, vec![], serde_json::json!({ "id": "channel-1" })),
+            (
+                429,
+                vec![("retry-after", "0.01")],
+                serde_json::json!({ "retry_after": 0.01, "global": false }),
+            ),
+        ])
+        .await;
+        let metrics = Arc::new(Metrics::new());
+        let notifier = DiscordNotifier::new(
+            DiscordClient::new(
+                reqwest::Client::new(),
+                base_url,
+                Some("token".to_string()),
+                metrics.clone(),
+            ),
+            vec!["user-1".to_string()],
+            DmChannelCache::in_memory(),
+            4,
+            Arc::new(NotificationTemplates::builtin()),
+        );
+
+        notifier.notify(&NotificationEvent::sample()).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            metrics.counter("discord_rate_limited_total", &[("scope", "user")]),
+            1
+        );
+    }
+
+    fn notifier(base_url: String, user_ids: &[&str], cache: DmChannelCache) -> DiscordNotifier {
+        DiscordNotifier::new(
+            DiscordClient::new(
+                reqwest::Client::new(),
+                base_url,
+                Some("token".to_string()),
+                Arc::new(Metrics::new()),
+            ),
+            user_ids.iter().map(|id| id.to_string()).collect(),
+            cache,
+            2,
+            Arc::new(NotificationTemplates::builtin()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_cached_channel_skips_lookup() {
+        let (base_url, calls) = fake_discord(vec![]).await;
+        let cache = DmChannelCache::in_memory();
+        cache.insert("user-1", "channel-1").await;
+
+        notifier(base_url, &["user-1"], cache)
+            .notify(&NotificationEvent::sample())
+            .await
+            .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_channel_invalidates_cache() {
+        let (base_url, calls) = fake_discord(vec![(
+            404,
+            vec![],
+            serde_json::json!({ "message": "Unknown Channel", "code": 10003 }),
+        )])
+        .await;
+        let cache = DmChannelCache::in_memory();
+        cache.insert("user-1", "stale").await;
+        let notifier = notifier(base_url, &["user-1"], cache);
+
+        notifier.notify(&NotificationEvent::sample()).await.unwrap();
+
+        // Stale send, channel lookup, then the send on the fresh channel
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            notifier.dm_channels.get("user-1").as_deref(),
+            Some("channel-1")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_notifies_every_recipient() {
+        let (base_url, calls) = fake_discord(vec![]).await;
+
+        notifier(
+            base_url,
+            &["user-1", "user-2", "user-3"],
+            DmChannelCache::in_memory(),
+        )
+        .notify(&NotificationEvent::sample())
+        .await
+        .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+    }
+}
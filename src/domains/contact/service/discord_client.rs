@@ -0,0 +1,488 @@
+use reqwest::{StatusCode, header::HeaderMap};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::common::{
+    errors::{AppError, AppResult},
+    metrics::Metrics,
+};
+
+pub const DISCORD_API_BASE_URL: &str = "https://discord.com/api/v10";
+
+/// Upper bound on a single rate-limit wait, past that the request is failed
+/// and left to the outbox retries.
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    remaining: u32,
+    reset_at: Instant,
+}
+
+/// Discord shares a bucket hash across major parameters, e.g. every channel's
+/// messages route reports the same hash, yet counts each of them separately.
+type BucketKey = (String, String);
+
+#[derive(Default)]
+struct RateLimits {
+    global_until: Option<Instant>,
+    /// Route key to the bucket hash Discord reported for it.
+    routes: HashMap<String, String>,
+    buckets: HashMap<BucketKey, Bucket>,
+}
+
+/// Discord REST client honouring per-route buckets and the global rate limit.
+///
+/// Requests sharing a route are queued behind each other, wait for their
+/// bucket to reset when it's exhausted, and are retried after a 429 once
+/// `retry_after` has elapsed.
+pub struct DiscordClient {
+    http: reqwest::Client,
+    base_url: String,
+    bot_token: Option<String>,
+    max_retries: u32,
+    limits: Mutex<RateLimits>,
+    queues: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    metrics: Arc<Metrics>,
+}
+
+impl DiscordClient {
+    pub fn new(
+        http: reqwest::Client,
+        base_url: impl Into<String>,
+        bot_token: Option<String>,
+        metrics: Arc<Metrics>,
+    ) -> Self {
+        Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            bot_token,
+            max_retries: 3,
+            limits: Mutex::new(RateLimits::default()),
+            queues: Mutex::new(HashMap::new()),
+            metrics,
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Queue for `route`. Queues nobody holds or waits on are dropped on the
+    /// way so routes with one-off IDs don't accumulate.
+    fn queue(&self, route: &str) -> Arc<tokio::sync::Mutex<()>> {
+        let mut queues = self.queues.lock().expect("discord queue lock poisoned");
+        queues.retain(|_, queue| Arc::strong_count(queue) > 1);
+        queues.entry(route.to_string()).or_default().clone()
+    }
+
+    /// How long to wait before hitting `route`, accounting for the global limit
+    /// and the route bucket.
+    fn wait_for(&self, route: &str, now: Instant) -> Option<Duration> {
+        let limits = self
+            .limits
+            .lock()
+            .expect("discord rate limit lock poisoned");
+        let global = limits.global_until.filter(|until| *until > now);
+        let bucket = limits
+            .routes
+            .get(route)
+            .and_then(|hash| limits.buckets.get(&bucket_key(hash, route)))
+            .filter(|bucket| bucket.remaining == 0 && bucket.reset_at > now)
+            .map(|bucket| bucket.reset_at);
+
+        global.max(bucket).map(|until| until - now)
+    }
+
+    fn record_headers(&self, route: &str, headers: &HeaderMap) {
+        let Some(hash) = header_str(headers, "x-ratelimit-bucket") else {
+            return;
+        };
+        let remaining = header_str(headers, "x-ratelimit-remaining").and_then(|v| v.parse().ok());
+        let reset_after = header_secs(headers, "x-ratelimit-reset-after");
+
+        if let (Some(remaining), Some(reset_after)) = (remaining, reset_after) {
+            let mut limits = self
+                .limits
+                .lock()
+                .expect("discord rate limit lock poisoned");
+            limits.routes.insert(route.to_string(), hash.to_string());
+            limits.buckets.insert(
+                bucket_key(hash, route),
+                Bucket {
+                    remaining,
+                    reset_at: Instant::now() + reset_after,
+                },
+            );
+            drop(limits);
+
+            self.metrics.set_gauge(
+                "discord_rate_limit_remaining",
+                &[("bucket", hash)],
+                remaining as f64,
+            );
+            tracing::debug!(
+                route,
+                bucket = hash,
+                remaining,
+                reset_after_ms = reset_after.as_millis() as u64,
+                "Discord rate limit state"
+            );
+        }
+    }
+
+    /// Sends a JSON `POST` to `url`, waiting out and retrying rate limits.
+    ///
+    /// `route` groups requests sharing a bucket, e.g. `"POST /channels/{id}/messages"`
+    /// with the channel ID filled in since it's a major parameter. Non-429
+    /// responses are returned as is for the caller to inspect.
+    #[tracing::instrument(skip(self, url, body))]
+    pub async fn post_json(
+        &self,
+        route: &str,
+        url: &str,
+        body: &serde_json::Value,
+    ) -> AppResult<reqwest::Response> {
+        let queue = self.queue(route);
+        let _turn = queue.lock().await;
+        let mut retries = 0;
+
+        loop {
+            if let Some(wait) = self.wait_for(route, Instant::now()) {
+                tracing::debug!(
+                    wait_ms = wait.as_millis() as u64,
+                    "Waiting for Discord rate limit"
+                );
+                tokio::time::sleep(wait).await;
+            }
+
+            let mut request = self.http.post(url).json(body);
+            if let Some(token) = &self.bot_token {
+                request = request.header("Authorization", format!("Bot {}", token));
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| AppError::DiscordApi(format!("Request failed: {}", e)))?;
+
+            self.metrics.incr(
+                "discord_requests_total",
+                &[("status", response.status().as_str())],
+            );
+            self.record_headers(route, response.headers());
+
+            if response.status() != StatusCode::TOO_MANY_REQUESTS {
+                return Ok(response);
+            }
+
+            let headers = response.headers().clone();
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            let retry_after = body["retry_after"]
+                .as_f64()
+                .map(Duration::from_secs_f64)
+                .or_else(|| header_secs(&headers, "retry-after"))
+                .unwrap_or(Duration::from_secs(1));
+            let global = body["global"].as_bool().unwrap_or(false)
+                || header_str(&headers, "x-ratelimit-global").is_some();
+            let scope = header_str(&headers, "x-ratelimit-scope").unwrap_or(if global {
+                "global"
+            } else {
+                "user"
+            });
+
+            self.metrics
+                .incr("discord_rate_limited_total", &[("scope", scope)]);
+            tracing::warn!(
+                route,
+                scope,
+                global,
+                retry_after_ms = retry_after.as_millis() as u64,
+                "Rate limited by Discord"
+            );
+
+            if global {
+                self.limits
+                    .lock()
+                    .expect("discord rate limit lock poisoned")
+                    .global_until = Some(Instant::now() + retry_after);
+            }
+
+            if retries >= self.max_retries || retry_after > MAX_RATE_LIMIT_WAIT {
+                return Err(AppError::DiscordApi(format!(
+                    "Rate limited on {} (retry after {:.1}s)",
+                    route,
+                    retry_after.as_secs_f64()
+                )));
+            }
+            retries += 1;
+            tokio::time::sleep(retry_after).await;
+        }
+    }
+}
+
+fn bucket_key(hash: &str, route: &str) -> BucketKey {
+    (hash.to_string(), major_parameter(route).to_string())
+}
+
+/// Channel, guild or webhook ID in `route`, empty when it has none.
+fn major_parameter(route: &str) -> &str {
+    let mut segments = route.split('/');
+    segments
+        .by_ref()
+        .find(|segment| matches!(*segment, "channels" | "guilds" | "webhooks"))
+        .and_then(|_| segments.next())
+        .unwrap_or_default()
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn header_secs(headers: &HeaderMap, name: &str) -> Option<Duration> {
+    header_str(headers, name)
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use axum::{
+        Json, Router,
+        http::{HeaderMap as AxumHeaders, StatusCode as AxumStatus},
+        response::IntoResponse,
+        routing::post,
+    };
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Status, headers and JSON body of a canned response.
+    pub(crate) type FakeResponse = (u16, Vec<(&'static str, &'static str)>, serde_json::Value);
+
+    /// Fake Discord answering `responses[n]` to the n-th call, then 200s.
+    pub(crate) async fn fake_discord(responses: Vec<FakeResponse>) -> (String, Arc<AtomicU32>) {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let responses = Arc::new(responses);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route(
+            "/{*path}",
+            post(move || {
+                let responses = responses.clone();
+                let call = counter.fetch_add(1, Ordering::SeqCst) as usize;
+                async move {
+                    let (status, headers, body) = responses.get(call).cloned().unwrap_or((
+                        200,
+                        Vec::new(),
+                        serde_json::json!({ "id": "channel-1" }),
+                    ));
+                    let mut header_map = AxumHeaders::new();
+                    for (name, value) in headers {
+                        header_map.insert(name, value.parse().unwrap());
+                    }
+                    (
+                        AxumStatus::from_u16(status).unwrap(),
+                        header_map,
+                        Json(body),
+                    )
+                        .into_response()
+                }
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", addr), calls)
+    }
+
+    fn client(base_url: String) -> (DiscordClient, Arc<Metrics>) {
+        let metrics = Arc::new(Metrics::new());
+        (
+            DiscordClient::new(
+                reqwest::Client::new(),
+                base_url,
+                Some("token".to_string()),
+                metrics.clone(),
+            ),
+            metrics,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_retries_after_429() {
+        let (base_url, calls) = fake_discord(vec![(
+            429,
+            vec![("retry-after", "0.05"), ("x-ratelimit-scope", "user")],
+            serde_json::json!({ "message": "You are being rate limited.", "retry_after": 0.05, "global": false }),
+        )])
+        .await;
+        let (client, metrics) = client(base_url);
+
+        let started = Instant::now();
+        let response = client
+            .post_json("POST /test", &client.url("/test"), &serde_json::json!({}))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        assert_eq!(
+            metrics.counter("discord_rate_limited_total", &[("scope", "user")]),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_idle_route_queues_are_evicted() {
+        let (base_url, _) = fake_discord(vec![]).await;
+        let (client, _) = client(base_url);
+
+        for channel in ["1", "2", "3"] {
+            let route = format!("POST /channels/{}/messages", channel);
+            client
+                .post_json(&route, &client.url("/test"), &serde_json::json!({}))
+                .await
+                .unwrap();
+        }
+
+        let _queue = client.queue("POST /channels/4/messages");
+        let queues = client.queues.lock().unwrap();
+        assert_eq!(queues.len(), 1);
+        assert!(queues.contains_key("POST /channels/4/messages"));
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_bucket_delays_next_request() {
+        let (base_url, _) = fake_discord(vec![(
+            200,
+            vec![
+                ("x-ratelimit-bucket", "abc"),
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset-after", "0.1"),
+            ],
+            serde_json::json!({}),
+        )])
+        .await;
+        let (client, metrics) = client(base_url);
+        let url = client.url("/test");
+
+        client
+            .post_json("POST /test", &url, &serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(
+            metrics.gauge("discord_rate_limit_remaining", &[("bucket", "abc")]),
+            Some(0.0)
+        );
+
+        let started = Instant::now();
+        client
+            .post_json("POST /test", &url, &serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(80));
+    }
+
+    #[tokio::test]
+    async fn test_buckets_are_scoped_by_major_parameter() {
+        let bucket = |remaining| {
+            (
+                200,
+                vec![
+                    ("x-ratelimit-bucket", "abc"),
+                    ("x-ratelimit-remaining", remaining),
+                    ("x-ratelimit-reset-after", "0.3"),
+                ],
+                serde_json::json!({}),
+            )
+        };
+        let (base_url, _) = fake_discord(vec![bucket("0"), bucket("4")]).await;
+        let (client, _) = client(base_url);
+        let url = client.url("/test");
+
+        // Both channels report the same hash, the second one must not
+        // overwrite the exhausted state of the first
+        for channel in ["1", "2"] {
+            let route = format!("POST /channels/{}/messages", channel);
+            client
+                .post_json(&route, &url, &serde_json::json!({}))
+                .await
+                .unwrap();
+        }
+
+        let now = Instant::now();
+        assert!(client.wait_for("POST /channels/1/messages", now).is_some());
+        assert_eq!(client.wait_for("POST /channels/2/messages", now), None);
+
+        let started = Instant::now();
+        client
+            .post_json("POST /channels/2/messages", &url, &serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_major_parameter() {
+        assert_eq!(major_parameter("POST /channels/42/messages"), "42");
+        assert_eq!(major_parameter("POST /users/@me/channels"), "");
+        assert_eq!(major_parameter("POST webhook"), "");
+    }
+
+    #[tokio::test]
+    async fn test_global_limit_blocks_other_routes() {
+        let (base_url, _) = fake_discord(vec![(
+            429,
+            vec![("x-ratelimit-global", "true")],
+            serde_json::json!({ "retry_after": 0.2, "global": true }),
+        )])
+        .await;
+        let (client, metrics) = client(base_url);
+        let client = Arc::new(client);
+
+        let limited = {
+            let client = client.clone();
+            tokio::spawn(async move {
+                client
+                    .post_json("POST /a", &client.url("/a"), &serde_json::json!({}))
+                    .await
+                    .unwrap();
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let started = Instant::now();
+        client
+            .post_json("POST /b", &client.url("/b"), &serde_json::json!({}))
+            .await
+            .unwrap();
+        limited.await.unwrap();
+
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert_eq!(
+            metrics.counter("discord_rate_limited_total", &[("scope", "global")]),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let limited = (
+            429,
+            vec![],
+            serde_json::json!({ "retry_after": 0.01, "global": false }),
+        );
+        let (base_url, calls) = fake_discord(vec![limited.clone(); 10]).await;
+        let (client, _) = client(base_url);
+
+        let result = client
+            .post_json("POST /test", &client.url("/test"), &serde_json::json!({}))
+            .await;
+
+        assert!(matches!(result, Err(AppError::DiscordApi(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+}
@@ -0,0 +1,182 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use super::{DiscordClient, Notification, NotificationEvent, NotificationTemplates, embed};
+use crate::common::errors::{AppError, AppResult};
+
+#[derive(Clone)]
+pub struct DiscordWebhookConfig {
+    /// Channel webhook URL, `https://discord.com/api/webhooks/{id}/{token}`.
+    pub url: String,
+    pub username: Option<String>,
+    pub avatar_url: Option<String>,
+    /// Posts into an existing thread of the channel.
+    pub thread_id: Option<String>,
+    /// Opens a new thread with this name per message, forum channels only.
+    pub thread_name: Option<String>,
+}
+
+/// Posts notifications to a Discord channel through an incoming webhook.
+///
+/// Unlike [`super::DiscordNotifier`] no bot is needed and a single request is
+/// made per message, whatever the number of people watching the channel.
+pub struct DiscordWebhookNotifier {
+    client: DiscordClient,
+    config: DiscordWebhookConfig,
+    templates: Arc<NotificationTemplates>,
+}
+
+impl DiscordWebhookNotifier {
+    /// `client` should carry no bot token, the webhook URL embeds its own.
+    pub fn new(
+        client: DiscordClient,
+        config: DiscordWebhookConfig,
+        templates: Arc<NotificationTemplates>,
+    ) -> Self {
+        Self {
+            client,
+            config,
+            templates,
+        }
+    }
+
+    fn payload(&self) -> serde_json::Value {
+        let mut payload = serde_json::json!({});
+        if let Some(username) = &self.config.username {
+            payload["username"] = username.clone().into();
+        }
+        if let Some(avatar_url) = &self.config.avatar_url {
+            payload["avatar_url"] = avatar_url.clone().into();
+        }
+        if let Some(thread_name) = &self.config.thread_name {
+            payload["thread_name"] = thread_name.clone().into();
+        }
+        payload
+    }
+}
+
+#[async_trait]
+impl Notification for DiscordWebhookNotifier {
+    fn name(&self) -> &str {
+        "discord_webhook"
+    }
+
+    #[tracing::instrument(skip(self, event), fields(request_id = %event.request_id()))]
+    async fn notify(&self, event: &NotificationEvent) -> AppResult<()> {
+        let mut url = reqwest::Url::parse(&self.config.url)
+            .map_err(|e| AppError::DiscordApi(format!("Invalid webhook URL: {}", e)))?;
+        url.query_pairs_mut().append_pair("wait", "true");
+        if let Some(thread_id) = &self.config.thread_id {
+            url.query_pairs_mut().append_pair("thread_id", thread_id);
+        }
+
+        embed::post_embed(
+            |payload| {
+                let url = url.as_str();
+                async move { self.client.post_json("POST webhook", url, &payload).await }
+            },
+            self.payload(),
+            &self.templates,
+            event,
+        )
+        .await?
+        .error_for_status()
+        .map_err(|e| AppError::DiscordApi(format!("Discord API error: {}", e)))?;
+
+        tracing::info!("Successfully sent Discord webhook notification");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::metrics::Metrics;
+    use axum::{
+        Json, Router,
+        extract::{Query, State},
+        http::StatusCode,
+        routing::post,
+    };
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    type Received = Arc<Mutex<Vec<(HashMap<String, String>, serde_json::Value)>>>;
+
+    async fn serve(status: StatusCode) -> (String, Received) {
+        let received = Received::default();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route(
+                "/api/webhooks/1/token",
+                post(
+                    move |State(received): State<Received>,
+                          Query(query): Query<HashMap<String, String>>,
+                          Json(body): Json<serde_json::Value>| async move {
+                        received.lock().unwrap().push((query, body));
+                        status
+                    },
+                ),
+            )
+            .with_state(received.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/api/webhooks/1/token", addr), received)
+    }
+
+    fn client() -> DiscordClient {
+        DiscordClient::new(reqwest::Client::new(), "", None, Arc::new(Metrics::new()))
+    }
+
+    fn config(url: String) -> DiscordWebhookConfig {
+        DiscordWebhookConfig {
+            url,
+            username: None,
+            avatar_url: None,
+            thread_id: None,
+            thread_name: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_posts_content_with_overrides() {
+        let (url, received) = serve(StatusCode::OK).await;
+        let notifier = DiscordWebhookNotifier::new(
+            client(),
+            DiscordWebhookConfig {
+                username: Some("Utazon".to_string()),
+                avatar_url: Some("https://utazon.fr/logo.png".to_string()),
+                thread_id: Some("42".to_string()),
+                ..config(url)
+            },
+            Arc::new(NotificationTemplates::builtin()),
+        );
+
+        notifier.notify(&NotificationEvent::sample()).await.unwrap();
+
+        let received = received.lock().unwrap();
+        let (query, body) = &received[0];
+        assert_eq!(query["wait"], "true");
+        assert_eq!(query["thread_id"], "42");
+        assert_eq!(body["embeds"][0]["fields"][3]["value"], "hello");
+        assert_eq!(body["username"], "Utazon");
+        assert_eq!(body["avatar_url"], "https://utazon.fr/logo.png");
+        assert!(body.get("thread_name").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_webhook_is_an_error() {
+        let (url, _) = serve(StatusCode::NOT_FOUND).await;
+        let notifier = DiscordWebhookNotifier::new(
+            client(),
+            config(url),
+            Arc::new(NotificationTemplates::builtin()),
+        );
+
+        let result = notifier.notify(&NotificationEvent::sample()).await;
+
+        assert!(matches!(result, Err(AppError::DiscordApi(_))));
+    }
+}
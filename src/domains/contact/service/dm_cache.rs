@@ -0,0 +1,127 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// User ID to DM channel ID map, so a notification costs one request per
+/// recipient instead of two.
+///
+/// When a path is given the map is loaded from and saved to a JSON file, which
+/// keeps the cache warm across restarts.
+pub struct DmChannelCache {
+    channels: Mutex<HashMap<String, String>>,
+    path: Option<PathBuf>,
+    save_lock: tokio::sync::Mutex<()>,
+}
+
+impl DmChannelCache {
+    pub fn in_memory() -> Self {
+        Self {
+            channels: Mutex::new(HashMap::new()),
+            path: None,
+            save_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// A corrupt file only costs a cold cache, so it's ignored rather than
+    /// failing startup.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let channels = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring unreadable DM channel cache {:?}: {}", path, e);
+                HashMap::new()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            channels: Mutex::new(channels),
+            path: Some(path),
+            save_lock: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    pub fn get(&self, user_id: &str) -> Option<String> {
+        self.channels
+            .lock()
+            .expect("dm cache lock poisoned")
+            .get(user_id)
+            .cloned()
+    }
+
+    pub async fn insert(&self, user_id: &str, channel_id: &str) {
+        self.channels
+            .lock()
+            .expect("dm cache lock poisoned")
+            .insert(user_id.to_string(), channel_id.to_string());
+        self.persist().await;
+    }
+
+    pub async fn remove(&self, user_id: &str) {
+        self.channels
+            .lock()
+            .expect("dm cache lock poisoned")
+            .remove(user_id);
+        self.persist().await;
+    }
+
+    /// Best effort, a lost write only costs an extra channel lookup.
+    async fn persist(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        // Snapshotting under the save lock keeps an older map from being written last
+        let _guard = self.save_lock.lock().await;
+        let snapshot = serde_json::to_vec(&*self.channels.lock().expect("dm cache lock poisoned"))
+            .expect("string map serializes to JSON");
+
+        // Written aside then renamed, a crash mid-write keeps the previous file
+        let tmp = path.with_extension("tmp");
+        let result = match tokio::fs::write(&tmp, snapshot).await {
+            Ok(()) => tokio::fs::rename(&tmp, path).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to persist DM channel cache to {:?}: {}", path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_persisted_cache_survives_reload() {
+        let path = std::env::temp_dir().join(format!("dm-cache-{}.json", uuid::Uuid::new_v4()));
+
+        let cache = DmChannelCache::load(&path).unwrap();
+        cache.insert("user-1", "channel-1").await;
+        cache.insert("user-2", "channel-2").await;
+        cache.remove("user-2").await;
+
+        let reloaded = DmChannelCache::load(&path).unwrap();
+        assert_eq!(reloaded.get("user-1").as_deref(), Some("channel-1"));
+        assert_eq!(reloaded.get("user-2"), None);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_cache_starts_empty() {
+        let path = std::env::temp_dir().join(format!("dm-cache-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "{not json").unwrap();
+
+        let cache = DmChannelCache::load(&path).unwrap();
+        assert_eq!(cache.get("user-1"), None);
+
+        cache.insert("user-1", "channel-1").await;
+        let reloaded = DmChannelCache::load(&path).unwrap();
+        assert_eq!(reloaded.get("user-1").as_deref(), Some("channel-1"));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,122 @@
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+use super::{
+    NotificationEvent,
+    sanitize::{self, MESSAGE_LIMIT},
+    templates::{NotificationTemplates, TemplateChannel},
+};
+use crate::common::errors::AppResult;
+
+/// Posts `base` extended with the event embed, and falls back to plain
+/// content, split in as many messages as needed, when Discord rejects the
+/// embed with a 400.
+///
+/// Visitor input is sanitized and mentions are disabled in both cases.
+/// `send` posts the given JSON payload, it's called once per message. The last
+/// response is returned unchecked so callers can react to specific statuses.
+pub(super) async fn post_embed<F>(
+    send: impl Fn(Value) -> F,
+    base: Value,
+    templates: &NotificationTemplates,
+    event: &NotificationEvent,
+) -> AppResult<reqwest::Response>
+where
+    F: Future<Output = AppResult<reqwest::Response>>,
+{
+    let event = sanitize::sanitize_event(event);
+    let mut base = base;
+    base["allowed_mentions"] = sanitize::no_mentions();
+
+    let mut rich = base.clone();
+    rich["embeds"] = json!([templates.discord_embed(&event)]);
+
+    let response = send(rich).await?;
+    if response.status() != StatusCode::BAD_REQUEST {
+        return Ok(response);
+    }
+
+    let reason = response.text().await.unwrap_or_default();
+    tracing::warn!(
+        "Discord rejected the embed, falling back to plain content: {}",
+        reason
+    );
+
+    let content = templates.render(TemplateChannel::Discord, &event)?;
+    let mut chunks = sanitize::split_content(&content, MESSAGE_LIMIT).into_iter();
+    loop {
+        let mut plain = base.clone();
+        plain["content"] = chunks.next().unwrap_or_default().into();
+        let response = send(plain).await?;
+        if chunks.len() == 0 || !response.status().is_success() {
+            return Ok(response);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::errors::AppError;
+    use axum::{Json, Router, extract::State, http::StatusCode, routing::post};
+    use std::sync::{Arc, Mutex};
+
+    /// Rejects any payload carrying embeds, like Discord does for an invalid one.
+    async fn embed_rejecting_server() -> (String, Arc<Mutex<Vec<Value>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app =
+            Router::new()
+                .route(
+                    "/messages",
+                    post(
+                        |State(received): State<Arc<Mutex<Vec<Value>>>>,
+                         Json(body): Json<Value>| async move {
+                            let status = if body.get("embeds").is_some() {
+                                StatusCode::BAD_REQUEST
+                            } else {
+                                StatusCode::OK
+                            };
+                            received.lock().unwrap().push(body);
+                            status
+                        },
+                    ),
+                )
+                .with_state(received.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/messages", addr), received)
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_plain_content() {
+        let (url, received) = embed_rejecting_server().await;
+        let client = reqwest::Client::new();
+
+        post_embed(
+            |payload| {
+                let request = client.post(&url);
+                async move { request.json(&payload).send().await.map_err(AppError::from) }
+            },
+            json!({ "username": "Utazon" }),
+            &NotificationTemplates::builtin(),
+            &NotificationEvent::sample(),
+        )
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert!(received[1].get("embeds").is_none());
+        assert_eq!(received[1]["allowed_mentions"]["parse"], json!([]));
+        assert_eq!(received[1]["username"], "Utazon");
+        assert!(
+            received[1]["content"]
+                .as_str()
+                .unwrap()
+                .contains("john@\u{200B}example.com")
+        );
+    }
+}
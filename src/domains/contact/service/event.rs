@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// Topic picked by the visitor, drives the embed colour on Discord.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContactCategory {
+    Project,
+    Quote,
+    Partnership,
+    Press,
+    #[default]
+    Other,
+}
+
+impl ContactCategory {
+    pub fn label(self) -> &'static str {
+        match self {
+            ContactCategory::Project => "Projet",
+            ContactCategory::Quote => "Devis",
+            ContactCategory::Partnership => "Partenariat",
+            ContactCategory::Press => "Presse",
+            ContactCategory::Other => "Autre",
+        }
+    }
+}
+
+/// Context of the request that raised an event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventMetadata {
+    pub request_id: String,
+    /// Unknown when the server isn't started with connection info, e.g. in tests.
+    pub client_ip: Option<IpAddr>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl EventMetadata {
+    pub fn new(request_id: String, client_ip: Option<IpAddr>) -> Self {
+        Self {
+            request_id,
+            client_ip,
+            occurred_at: Utc::now(),
+        }
+    }
+}
+
+/// A visitor sent the contact form, fields are already validated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactSubmitted {
+    pub metadata: EventMetadata,
+    pub category: ContactCategory,
+    pub first_name: String,
+    pub last_name: String,
+    pub phone: String,
+    pub email: String,
+    pub message: String,
+    /// Request ID of an earlier submission with the same email and a similar
+    /// message, see [`crate::domains::contact::dedup`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+}
+
+/// Event handed to the notifiers, each channel renders it with its own
+/// template, see [`super::templates`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum NotificationEvent {
+    ContactSubmitted(ContactSubmitted),
+}
+
+impl NotificationEvent {
+    /// Dotted name exposed to webhook consumers.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            NotificationEvent::ContactSubmitted(_) => "contact.submitted",
+        }
+    }
+
+    pub fn metadata(&self) -> &EventMetadata {
+        match self {
+            NotificationEvent::ContactSubmitted(contact) => &contact.metadata,
+        }
+    }
+
+    pub fn request_id(&self) -> &str {
+        &self.metadata().request_id
+    }
+
+    #[cfg(test)]
+    pub(crate) fn sample() -> Self {
+        NotificationEvent::ContactSubmitted(ContactSubmitted::sample())
+    }
+}
+
+impl From<ContactSubmitted> for NotificationEvent {
+    fn from(contact: ContactSubmitted) -> Self {
+        NotificationEvent::ContactSubmitted(contact)
+    }
+}
+
+#[cfg(test)]
+impl ContactSubmitted {
+    pub(crate) fn sample() -> Self {
+        Self {
+            metadata: EventMetadata::new("req-1".to_string(), Some([203, 0, 113, 7].into())),
+            category: ContactCategory::Project,
+            first_name: "John".to_string(),
+            last_name: "Doe".to_string(),
+            phone: "+1234567890".to_string(),
+            email: "john@example.com".to_string(),
+            message: "hello".to_string(),
+            duplicate_of: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_round_trips_with_its_tag() {
+        let json = serde_json::to_value(NotificationEvent::sample()).unwrap();
+        assert_eq!(json["event"], "contact_submitted");
+        assert_eq!(json["metadata"]["client_ip"], "203.0.113.7");
+
+        let event: NotificationEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(event.request_id(), "req-1");
+        assert_eq!(event.kind(), "contact.submitted");
+    }
+}
@@ -0,0 +1,363 @@
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use super::{Notification, NotificationEvent};
+use crate::common::errors::{AppError, AppResult};
+
+/// How many channels must succeed for a notification to count as delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "required", rename_all = "snake_case")]
+pub enum DeliveryPolicy {
+    /// Every channel must succeed.
+    All,
+    /// At least one channel must succeed.
+    Any,
+    /// At least this many channels must succeed.
+    Quorum(usize),
+    /// Channels are tried in order and the first success stops the dispatch.
+    PrimaryWithFallback,
+}
+
+impl std::str::FromStr for DeliveryPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "all" => Ok(DeliveryPolicy::All),
+            "any" => Ok(DeliveryPolicy::Any),
+            "primary" | "primary-with-fallback" => Ok(DeliveryPolicy::PrimaryWithFallback),
+            other => match other.strip_prefix("quorum:").map(str::parse) {
+                Some(Ok(required)) if required > 0 => Ok(DeliveryPolicy::Quorum(required)),
+                _ => Err(format!("unknown delivery policy '{}'", other)),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "error", rename_all = "snake_case")]
+pub enum ChannelOutcome {
+    Delivered,
+    Failed(String),
+    /// Not attempted because the policy was already satisfied.
+    Skipped,
+    /// Delivered by a previous attempt, not sent again.
+    AlreadyDelivered,
+}
+
+impl ChannelOutcome {
+    pub fn is_delivered(&self) -> bool {
+        matches!(
+            self,
+            ChannelOutcome::Delivered | ChannelOutcome::AlreadyDelivered
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelReport {
+    pub channel: String,
+    #[serde(flatten)]
+    pub outcome: ChannelOutcome,
+    pub elapsed_ms: u64,
+}
+
+impl ChannelReport {
+    pub fn new(channel: &str, result: &AppResult<()>, elapsed: Duration) -> Self {
+        Self {
+            channel: channel.to_string(),
+            outcome: match result {
+                Ok(()) => ChannelOutcome::Delivered,
+                Err(e) => ChannelOutcome::Failed(e.to_string()),
+            },
+            elapsed_ms: elapsed.as_millis() as u64,
+        }
+    }
+
+    pub fn already_delivered(channel: &str) -> Self {
+        Self {
+            channel: channel.to_string(),
+            outcome: ChannelOutcome::AlreadyDelivered,
+            elapsed_ms: 0,
+        }
+    }
+}
+
+/// Per-channel outcome of a notification, along with the policy verdict.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliveryReport {
+    pub policy: DeliveryPolicy,
+    pub delivered: bool,
+    pub channels: Vec<ChannelReport>,
+}
+
+impl DeliveryReport {
+    pub fn new(policy: DeliveryPolicy, channels: Vec<ChannelReport>) -> Self {
+        let successes = channels.iter().filter(|c| c.outcome.is_delivered()).count();
+        let delivered = match policy {
+            DeliveryPolicy::All => successes == channels.len(),
+            DeliveryPolicy::Any | DeliveryPolicy::PrimaryWithFallback => successes > 0,
+            DeliveryPolicy::Quorum(required) => successes >= required,
+        };
+
+        Self {
+            policy,
+            delivered,
+            channels,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        self.channels
+            .iter()
+            .map(|c| match &c.outcome {
+                ChannelOutcome::Delivered => format!("{}: delivered", c.channel),
+                ChannelOutcome::Failed(e) => format!("{}: failed ({})", c.channel, e),
+                ChannelOutcome::Skipped => format!("{}: skipped", c.channel),
+                ChannelOutcome::AlreadyDelivered => format!("{}: already delivered", c.channel),
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Channels that got the notification, now or in a previous attempt.
+    pub fn delivered_channels(&self) -> Vec<String> {
+        self.channels
+            .iter()
+            .filter(|c| c.outcome.is_delivered())
+            .map(|c| c.channel.clone())
+            .collect()
+    }
+
+    pub fn into_result(self) -> AppResult<Self> {
+        if self.delivered {
+            Ok(self)
+        } else {
+            Err(AppError::Notification(self.summary()))
+        }
+    }
+}
+
+/// Dispatches every notification to several channels according to a [`DeliveryPolicy`].
+pub struct FanoutNotifier {
+    channels: Vec<Arc<dyn Notification>>,
+    policy: DeliveryPolicy,
+}
+
+impl FanoutNotifier {
+    pub fn new(channels: Vec<Arc<dyn Notification>>, policy: DeliveryPolicy) -> Self {
+        Self { channels, policy }
+    }
+
+    async fn attempt(channel: &dyn Notification, event: &NotificationEvent) -> ChannelReport {
+        let started = Instant::now();
+        let result = channel.notify(event).await;
+        ChannelReport::new(channel.name(), &result, started.elapsed())
+    }
+}
+
+#[async_trait]
+impl Notification for FanoutNotifier {
+    fn name(&self) -> &str {
+        "fanout"
+    }
+
+    async fn notify(&self, event: &NotificationEvent) -> AppResult<()> {
+        self.deliver(event, &[]).await.into_result().map(|_| ())
+    }
+
+    #[tracing::instrument(skip(self, event, already_delivered), fields(request_id = %event.request_id()))]
+    async fn deliver(
+        &self,
+        event: &NotificationEvent,
+        already_delivered: &[String],
+    ) -> DeliveryReport {
+        let is_done = |channel: &Arc<dyn Notification>| {
+            already_delivered.iter().any(|name| name == channel.name())
+        };
+        let reports = match self.policy {
+            DeliveryPolicy::PrimaryWithFallback => {
+                let mut reports = Vec::with_capacity(self.channels.len());
+                let mut delivered = false;
+                for channel in &self.channels {
+                    if is_done(channel) {
+                        reports.push(ChannelReport::already_delivered(channel.name()));
+                        delivered = true;
+                        continue;
+                    }
+                    if delivered {
+                        reports.push(ChannelReport {
+                            channel: channel.name().to_string(),
+                            outcome: ChannelOutcome::Skipped,
+                            elapsed_ms: 0,
+                        });
+                        continue;
+                    }
+                    let report = Self::attempt(channel.as_ref(), event).await;
+                    delivered = report.outcome == ChannelOutcome::Delivered;
+                    reports.push(report);
+                }
+                reports
+            }
+            _ => {
+                join_all(self.channels.iter().map(|channel| async move {
+                    if is_done(channel) {
+                        ChannelReport::already_delivered(channel.name())
+                    } else {
+                        Self::attempt(channel.as_ref(), event).await
+                    }
+                }))
+                .await
+            }
+        };
+
+        let report = DeliveryReport::new(self.policy, reports);
+        let summary = report.summary();
+        if report.delivered {
+            tracing::info!(policy = ?report.policy, "Notification delivered: {}", summary);
+        } else {
+            tracing::error!(policy = ?report.policy, "Notification not delivered: {}", summary);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    struct FakeChannel {
+        name: &'static str,
+        fail: bool,
+        calls: AtomicU32,
+    }
+
+    impl FakeChannel {
+        fn new(name: &'static str, fail: bool) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                fail,
+                calls: AtomicU32::new(0),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Notification for FakeChannel {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn notify(&self, _event: &NotificationEvent) -> AppResult<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                Err(AppError::Email("boom".to_string()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    async fn dispatch(channels: &[Arc<FakeChannel>], policy: DeliveryPolicy) -> DeliveryReport {
+        let channels = channels
+            .iter()
+            .map(|c| c.clone() as Arc<dyn Notification>)
+            .collect();
+        FanoutNotifier::new(channels, policy)
+            .deliver(&NotificationEvent::sample(), &[])
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_all_requires_every_channel() {
+        let channels = [FakeChannel::new("a", false), FakeChannel::new("b", true)];
+        let report = dispatch(&channels, DeliveryPolicy::All).await;
+
+        assert!(!report.delivered);
+        assert_eq!(report.channels[0].outcome, ChannelOutcome::Delivered);
+        assert!(matches!(
+            report.channels[1].outcome,
+            ChannelOutcome::Failed(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_any_and_quorum() {
+        let channels = [
+            FakeChannel::new("a", false),
+            FakeChannel::new("b", true),
+            FakeChannel::new("c", false),
+        ];
+
+        assert!(dispatch(&channels, DeliveryPolicy::Any).await.delivered);
+        assert!(
+            dispatch(&channels, DeliveryPolicy::Quorum(2))
+                .await
+                .delivered
+        );
+        assert!(
+            !dispatch(&channels, DeliveryPolicy::Quorum(3))
+                .await
+                .delivered
+        );
+    }
+
+    #[tokio::test]
+    async fn test_primary_with_fallback_stops_at_first_success() {
+        let channels = [
+            FakeChannel::new("primary", true),
+            FakeChannel::new("fallback", false),
+            FakeChannel::new("last", false),
+        ];
+        let report = dispatch(&channels, DeliveryPolicy::PrimaryWithFallback).await;
+
+        assert!(report.delivered);
+        assert_eq!(report.channels[1].outcome, ChannelOutcome::Delivered);
+        assert_eq!(report.channels[2].outcome, ChannelOutcome::Skipped);
+        assert_eq!(channels[2].calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_already_delivered_channels_are_not_notified_again() {
+        let channels = [FakeChannel::new("a", false), FakeChannel::new("b", false)];
+        let fanout = FanoutNotifier::new(
+            channels
+                .iter()
+                .map(|c| c.clone() as Arc<dyn Notification>)
+                .collect(),
+            DeliveryPolicy::All,
+        );
+
+        let report = fanout
+            .deliver(&NotificationEvent::sample(), &["a".to_string()])
+            .await;
+
+        assert!(report.delivered);
+        assert_eq!(report.channels[0].outcome, ChannelOutcome::AlreadyDelivered);
+        assert_eq!(channels[0].calls.load(Ordering::SeqCst), 0);
+        assert_eq!(channels[1].calls.load(Ordering::SeqCst), 1);
+        assert_eq!(report.delivered_channels(), ["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_undelivered_report_is_an_error() {
+        let channels = [FakeChannel::new("a", true)];
+        let result = dispatch(&channels, DeliveryPolicy::Any).await.into_result();
+
+        assert!(matches!(result, Err(AppError::Notification(msg)) if msg.contains("a: failed")));
+    }
+
+    #[test]
+    fn test_parse_policy() {
+        assert_eq!("ALL".parse(), Ok(DeliveryPolicy::All));
+        assert_eq!("quorum:2".parse(), Ok(DeliveryPolicy::Quorum(2)));
+        assert_eq!("primary".parse(), Ok(DeliveryPolicy::PrimaryWithFallback));
+        assert!("quorum:0".parse::<DeliveryPolicy>().is_err());
+        assert!("most".parse::<DeliveryPolicy>().is_err());
+    }
+}
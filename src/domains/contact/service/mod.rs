@@ -0,0 +1,143 @@
+mod discord;
+mod discord_client;
+mod discord_webhook;
+mod dm_cache;
+mod embed;
+mod event;
+mod fanout;
+mod sanitize;
+mod smtp;
+pub mod templates;
+mod webhook;
+
+use async_trait::async_trait;
+use std::{sync::Arc, time::Instant};
+
+use crate::common::{errors::AppResult, metrics::Metrics};
+
+pub use discord::{DiscordConfig, DiscordNotifier};
+pub use discord_client::{DISCORD_API_BASE_URL, DiscordClient};
+pub use discord_webhook::{DiscordWebhookConfig, DiscordWebhookNotifier};
+pub use dm_cache::DmChannelCache;
+pub use event::{ContactCategory, ContactSubmitted, EventMetadata, NotificationEvent};
+pub use fanout::{ChannelOutcome, ChannelReport, DeliveryPolicy, DeliveryReport, FanoutNotifier};
+pub use smtp::{SmtpConfig, SmtpNotifier, SmtpTls};
+pub use templates::{NotificationTemplates, TemplateChannel};
+pub use webhook::{WebhookConfig, WebhookEndpoint, WebhookNotifier};
+
+#[async_trait]
+pub trait Notification: Send + Sync {
+    /// Channel name used in delivery reports.
+    fn name(&self) -> &str;
+
+    async fn notify(&self, event: &NotificationEvent) -> AppResult<()>;
+
+    /// Notifies and reports the outcome of every channel involved. Channels
+    /// named in `already_delivered` got the event in a previous attempt and
+    /// are not notified again.
+    async fn deliver(
+        &self,
+        event: &NotificationEvent,
+        already_delivered: &[String],
+    ) -> DeliveryReport {
+        if already_delivered.iter().any(|name| name == self.name()) {
+            return DeliveryReport::new(
+                DeliveryPolicy::All,
+                vec![ChannelReport::already_delivered(self.name())],
+            );
+        }
+        let started = Instant::now();
+        let result = self.notify(event).await;
+        DeliveryReport::new(
+            DeliveryPolicy::All,
+            vec![ChannelReport::new(self.name(), &result, started.elapsed())],
+        )
+    }
+}
+
+/// Notification channel, one per entry of the `NOTIFIER` environment variable.
+#[derive(Clone)]
+pub enum NotifierConfig {
+    Discord(DiscordConfig),
+    DiscordWebhook(DiscordWebhookConfig),
+    Smtp(SmtpConfig),
+    Webhook(WebhookConfig),
+}
+
+/// Builds the configured channel, or a [`FanoutNotifier`] when several are set.
+pub fn build_notifier(
+    configs: &[NotifierConfig],
+    policy: DeliveryPolicy,
+    http_client: reqwest::Client,
+    metrics: Arc<Metrics>,
+    templates: Arc<NotificationTemplates>,
+) -> anyhow::Result<Arc<dyn Notification>> {
+    if let DeliveryPolicy::Quorum(required) = policy
+        && required > configs.len()
+    {
+        anyhow::bail!(
+            "NOTIFIER_POLICY quorum of {} exceeds the {} configured notifier(s)",
+            required,
+            configs.len()
+        );
+    }
+
+    let mut channels = configs
+        .iter()
+        .map(|config| {
+            build_channel(
+                config,
+                http_client.clone(),
+                metrics.clone(),
+                templates.clone(),
+            )
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    // The outbox remembers delivered channels by name
+    for (i, channel) in channels.iter().enumerate() {
+        if channels[..i].iter().any(|c| c.name() == channel.name()) {
+            anyhow::bail!("NOTIFIER lists '{}' more than once", channel.name());
+        }
+    }
+
+    match channels.len() {
+        0 => anyhow::bail!("at least one notifier is required"),
+        1 => Ok(channels.remove(0)),
+        _ => Ok(Arc::new(FanoutNotifier::new(channels, policy))),
+    }
+}
+
+fn build_channel(
+    config: &NotifierConfig,
+    http_client: reqwest::Client,
+    metrics: Arc<Metrics>,
+    templates: Arc<NotificationTemplates>,
+) -> anyhow::Result<Arc<dyn Notification>> {
+    Ok(match config {
+        NotifierConfig::Discord(discord) => Arc::new(DiscordNotifier::new(
+            DiscordClient::new(
+                http_client,
+                &discord.api_base_url,
+                Some(discord.bot_token.clone()),
+                metrics,
+            ),
+            discord.user_ids.clone(),
+            match &discord.dm_cache_path {
+                Some(path) => DmChannelCache::load(path)?,
+                None => DmChannelCache::in_memory(),
+            },
+            discord.max_concurrency,
+            templates,
+        )),
+        NotifierConfig::DiscordWebhook(webhook) => Arc::new(DiscordWebhookNotifier::new(
+            DiscordClient::new(http_client, DISCORD_API_BASE_URL, None, metrics),
+            webhook.clone(),
+            templates,
+        )),
+        NotifierConfig::Smtp(smtp) => Arc::new(SmtpNotifier::new(smtp, templates)?),
+        NotifierConfig::Webhook(webhook) => {
+            Arc::new(WebhookNotifier::new(http_client, webhook.clone()))
+        }
+    })
+}
@@ -0,0 +1,217 @@
+use serde_json::{Value, json};
+
+use super::{ContactSubmitted, NotificationEvent};
+
+/// Discord rejects message contents longer than this.
+pub const MESSAGE_LIMIT: usize = 2000;
+
+/// Discord rejects embed field values longer than this.
+pub const FIELD_LIMIT: usize = 1024;
+
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+
+/// `allowed_mentions` value disabling every ping, whatever the content says.
+pub fn no_mentions() -> Value {
+    json!({ "parse": [] })
+}
+
+/// Copy of `event` whose visitor supplied fields are safe to interpolate into
+/// Discord markdown.
+pub fn sanitize_event(event: &NotificationEvent) -> NotificationEvent {
+    match event {
+        NotificationEvent::ContactSubmitted(contact) => {
+            NotificationEvent::ContactSubmitted(ContactSubmitted {
+                first_name: sanitize(&contact.first_name),
+                last_name: sanitize(&contact.last_name),
+                phone: sanitize(&contact.phone),
+                email: sanitize(&contact.email),
+                message: sanitize(&contact.message),
+                ..contact.clone()
+            })
+        }
+    }
+}
+
+/// Defangs URLs, then escapes markdown and breaks mentions.
+pub fn sanitize(text: &str) -> String {
+    neutralize_mentions(&escape_markdown(&defang_urls(text)))
+}
+
+/// Backslash-escapes the characters Discord interprets as markdown, including
+/// the brackets and parentheses of masked links.
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        // Headings, quotes and lists only trigger at the start of a line
+        let trimmed = line.trim_start_matches(' ');
+        escaped.push_str(&line[..line.len() - trimmed.len()]);
+        if trimmed.starts_with(['#', '-']) {
+            escaped.push('\\');
+        }
+        for c in trimmed.chars() {
+            if matches!(
+                c,
+                '\\' | '*' | '_' | '~' | '`' | '|' | '[' | ']' | '(' | ')' | '<' | '>'
+            ) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Inserts a zero width space after `@` so `@everyone`, `@here` and raw user
+/// mentions render as plain text even if `allowed_mentions` were ignored.
+pub fn neutralize_mentions(text: &str) -> String {
+    text.replace('@', &format!("@{}", ZERO_WIDTH_SPACE))
+}
+
+/// Rewrites `http(s)://host.tld` as `hxxp(s)://host[.]tld` so links are
+/// neither clickable nor previewed.
+pub fn defang_urls(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = find_url(rest) {
+        output.push_str(&rest[..start]);
+        let url = &rest[start..];
+        let end = url
+            .find(|c: char| c.is_whitespace() || c == '<' || c == '>')
+            .unwrap_or(url.len());
+        let (url, tail) = url.split_at(end);
+
+        let (scheme, address) = url.split_once("://").expect("find_url matched a scheme");
+        let (host, path) = address
+            .find(['/', '?', '#'])
+            .map(|i| address.split_at(i))
+            .unwrap_or((address, ""));
+        output.push_str(&scheme.to_ascii_lowercase().replacen("tt", "xx", 1));
+        output.push_str("://");
+        output.push_str(&host.replace('.', "[.]"));
+        output.push_str(path);
+
+        rest = tail;
+    }
+
+    output.push_str(rest);
+    output
+}
+
+fn find_url(text: &str) -> Option<usize> {
+    let lower = text.to_ascii_lowercase();
+    [lower.find("http://"), lower.find("https://")]
+        .into_iter()
+        .flatten()
+        .min()
+}
+
+/// Splits `text` into chunks of at most `limit` characters, preferring line
+/// breaks, then spaces, and only cutting words as a last resort.
+///
+/// A chunk never ends on an unescaped backslash, which would leave the
+/// character it escapes unescaped at the start of the next chunk.
+pub fn split_content(text: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+
+    while rest.chars().count() > limit {
+        let hard_cut = rest
+            .char_indices()
+            .nth(limit)
+            .map(|(i, _)| i)
+            .unwrap_or(rest.len());
+        let window = &rest[..hard_cut];
+        let mut cut = window
+            .rfind('\n')
+            .or_else(|| window.rfind(' '))
+            .filter(|&i| i > 0)
+            .unwrap_or(hard_cut);
+        if cut > 1 && ends_with_escape(&rest[..cut]) {
+            cut -= 1;
+        }
+
+        chunks.push(rest[..cut].to_string());
+        rest = rest[cut..]
+            .strip_prefix(['\n', ' '])
+            .unwrap_or(&rest[cut..]);
+    }
+
+    if !rest.is_empty() || chunks.is_empty() {
+        chunks.push(rest.to_string());
+    }
+    chunks
+}
+
+/// Whether `text` ends with a backslash that isn't itself escaped.
+fn ends_with_escape(text: &str) -> bool {
+    text.bytes().rev().take_while(|&b| b == b'\\').count() % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_markdown() {
+        assert_eq!(escape_markdown("**bold** _it_"), r"\*\*bold\*\* \_it\_");
+        assert_eq!(
+            escape_markdown("[click](https://evil.com)"),
+            r"\[click\]\(https://evil.com\)"
+        );
+        assert_eq!(escape_markdown("# title\n> quote"), "\\# title\n\\> quote");
+    }
+
+    #[test]
+    fn test_neutralize_mentions() {
+        let text = neutralize_mentions("hi @everyone and <@123>");
+        assert!(!text.contains("@everyone"));
+        assert!(!text.contains("<@1"));
+    }
+
+    #[test]
+    fn test_defang_urls() {
+        assert_eq!(
+            defang_urls("see https://evil.example.com/path?a=1 now"),
+            "see hxxps://evil[.]example[.]com/path?a=1 now"
+        );
+        assert_eq!(defang_urls("HTTP://a.b"), "hxxp://a[.]b");
+        assert_eq!(defang_urls("no links here."), "no links here.");
+    }
+
+    #[test]
+    fn test_masked_link_is_fully_disarmed() {
+        let text = sanitize("[free](https://evil.com)");
+        assert!(!text.contains("https://"));
+        assert!(text.contains(r"\[free\]"));
+    }
+
+    #[test]
+    fn test_split_content_prefers_line_breaks() {
+        let text = format!("{}\n{}", "a".repeat(1500), "b".repeat(1500));
+        let chunks = split_content(&text, MESSAGE_LIMIT);
+        assert_eq!(chunks, vec!["a".repeat(1500), "b".repeat(1500)]);
+    }
+
+    #[test]
+    fn test_split_content_hard_cuts_long_words() {
+        let chunks = split_content(&"é".repeat(4500), MESSAGE_LIMIT);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.chars().count() <= MESSAGE_LIMIT));
+        assert_eq!(chunks.concat(), "é".repeat(4500));
+    }
+
+    #[test]
+    fn test_split_content_keeps_escapes_together() {
+        let text = format!(r"{}\*{}", "a".repeat(9), "b".repeat(5));
+        let chunks = split_content(&text, 10);
+        assert_eq!(chunks[0], "a".repeat(9));
+        assert!(chunks[1].starts_with(r"\*"));
+        assert_eq!(chunks.concat(), text);
+
+        let text = format!(r"{}\\{}", "a".repeat(8), "b".repeat(5));
+        let chunks = split_content(&text, 10);
+        assert_eq!(chunks[0], format!(r"{}\\", "a".repeat(8)));
+        assert_eq!(chunks.concat(), text);
+    }
+}
@@ -0,0 +1,230 @@
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, MultiPart},
+    transport::smtp::authentication::Credentials,
+};
+use std::sync::Arc;
+
+use super::{Notification, NotificationEvent, NotificationTemplates};
+use crate::common::errors::{AppError, AppResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    /// Plain text connection, only meant for local sinks such as MailHog.
+    None,
+    /// Plain connection upgraded with STARTTLS, usually port 587.
+    StartTls,
+    /// TLS from the first byte, usually port 465.
+    Implicit,
+}
+
+impl std::str::FromStr for SmtpTls {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(SmtpTls::None),
+            "starttls" => Ok(SmtpTls::StartTls),
+            "tls" | "implicit" => Ok(SmtpTls::Implicit),
+            other => Err(format!("unknown SMTP TLS mode '{}'", other)),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub tls: SmtpTls,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+pub struct SmtpNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    templates: Arc<NotificationTemplates>,
+}
+
+impl SmtpNotifier {
+    pub fn new(config: &SmtpConfig, templates: Arc<NotificationTemplates>) -> anyhow::Result<Self> {
+        let builder = match config.tls {
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+            SmtpTls::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?
+            }
+            SmtpTls::Implicit => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+        }
+        .port(config.port);
+
+        let builder = match (&config.username, &config.password) {
+            (Some(username), Some(password)) => {
+                builder.credentials(Credentials::new(username.clone(), password.clone()))
+            }
+            (None, None) => builder,
+            _ => anyhow::bail!("SMTP username and password must be set together"),
+        };
+
+        let to = config
+            .to
+            .iter()
+            .map(|address| address.parse())
+            .collect::<Result<Vec<Mailbox>, _>>()?;
+        if to.is_empty() {
+            anyhow::bail!("at least one SMTP recipient is required");
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: config.from.parse()?,
+            to,
+            templates,
+        })
+    }
+
+    fn build_email(&self, event: &NotificationEvent) -> AppResult<Message> {
+        let content = self.templates.email(event)?;
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(content.subject);
+        for recipient in &self.to {
+            builder = builder.to(recipient.clone());
+        }
+
+        builder
+            .multipart(MultiPart::alternative_plain_html(
+                content.text,
+                content.html,
+            ))
+            .map_err(|e| AppError::Email(format!("Failed to build email: {}", e)))
+    }
+}
+
+#[async_trait]
+impl Notification for SmtpNotifier {
+    fn name(&self) -> &str {
+        "smtp"
+    }
+
+    #[tracing::instrument(skip(self, event), fields(request_id = %event.request_id()))]
+    async fn notify(&self, event: &NotificationEvent) -> AppResult<()> {
+        let email = self.build_email(event)?;
+
+        self.transport
+            .send(email)
+            .await
+            .map_err(|e| AppError::Email(format!("Failed to send email: {}", e)))?;
+
+        tracing::info!("Sent email notification to {} recipient(s)", self.to.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+        sync::oneshot,
+    };
+
+    #[test]
+    fn test_parse_tls_mode() {
+        assert_eq!("STARTTLS".parse::<SmtpTls>(), Ok(SmtpTls::StartTls));
+        assert_eq!("tls".parse::<SmtpTls>(), Ok(SmtpTls::Implicit));
+        assert!("ssl3".parse::<SmtpTls>().is_err());
+    }
+
+    /// Minimal SMTP sink accepting a single message, in the spirit of MailHog.
+    async fn smtp_sink() -> (u16, oneshot::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let (read, mut write) = socket.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let mut data = String::new();
+            let mut in_data = false;
+
+            write.write_all(b"220 sink ESMTP\r\n").await.unwrap();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                if in_data {
+                    if line == "." {
+                        in_data = false;
+                        write.write_all(b"250 queued\r\n").await.unwrap();
+                    } else {
+                        data.push_str(&line);
+                        data.push('\n');
+                    }
+                    continue;
+                }
+
+                let command = line.to_ascii_uppercase();
+                let reply: &[u8] = if command.starts_with("DATA") {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if command.starts_with("QUIT") {
+                    write.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                write.write_all(reply).await.unwrap();
+            }
+
+            let _ = tx.send(data);
+        });
+
+        (port, rx)
+    }
+
+    #[tokio::test]
+    async fn test_notify_delivers_multipart_email() {
+        let (port, received) = smtp_sink().await;
+
+        let notifier = SmtpNotifier::new(
+            &SmtpConfig {
+                host: "127.0.0.1".to_string(),
+                port,
+                tls: SmtpTls::None,
+                username: None,
+                password: None,
+                from: "Utazon <noreply@utazon.fr>".to_string(),
+                to: vec!["team@utazon.fr".to_string()],
+            },
+            Arc::new(NotificationTemplates::builtin()),
+        )
+        .unwrap();
+
+        notifier.notify(&NotificationEvent::sample()).await.unwrap();
+        drop(notifier);
+
+        let data = received.await.unwrap();
+        assert!(data.contains("To: team@utazon.fr"));
+        assert!(data.contains("multipart/alternative"));
+        assert!(data.contains("text/plain"));
+        assert!(data.contains("text/html"));
+        assert!(data.contains("Email: john@example.com"));
+    }
+
+    #[test]
+    fn test_partial_credentials_are_rejected() {
+        let config = SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port: 25,
+            tls: SmtpTls::None,
+            username: Some("user".to_string()),
+            password: None,
+            from: "Utazon <noreply@utazon.fr>".to_string(),
+            to: vec!["team@utazon.fr".to_string()],
+        };
+
+        assert!(SmtpNotifier::new(&config, Arc::new(NotificationTemplates::builtin())).is_err());
+    }
+}
@@ -0,0 +1,712 @@
+//! Per-channel renderings of a [`NotificationEvent`].
+//!
+//! Text channels go through MiniJinja templates, one file per channel and per
+//! locale, e.g. `fr/discord.md.j2`. The Discord embed takes its labels from
+//! `{locale}/embed.json`. Defaults are compiled in and can be overridden from
+//! `NOTIFICATION_TEMPLATES_DIR`, which uses the same layout. Every template is
+//! rendered against sample data and every label table is checked at startup so
+//! a typo fails the boot instead of a notification.
+
+use minijinja::{Environment, UndefinedBehavior, context};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use super::{
+    ContactCategory, ContactSubmitted, EventMetadata, NotificationEvent,
+    sanitize::{self, FIELD_LIMIT},
+};
+use crate::common::errors::{AppError, AppResult};
+
+/// Locale of the compiled-in templates, used when the configured locale lacks one.
+pub const DEFAULT_LOCALE: &str = "fr";
+
+const BUILTIN: &[(&str, TemplateChannel, &str)] = &[
+    (
+        "fr",
+        TemplateChannel::Discord,
+        include_str!("../../../../templates/notifications/fr/discord.md.j2"),
+    ),
+    (
+        "fr",
+        TemplateChannel::EmailSubject,
+        include_str!("../../../../templates/notifications/fr/email_subject.txt.j2"),
+    ),
+    (
+        "fr",
+        TemplateChannel::EmailBody,
+        include_str!("../../../../templates/notifications/fr/email_body.md.j2"),
+    ),
+    (
+        "en",
+        TemplateChannel::Discord,
+        include_str!("../../../../templates/notifications/en/discord.md.j2"),
+    ),
+    (
+        "en",
+        TemplateChannel::EmailSubject,
+        include_str!("../../../../templates/notifications/en/email_subject.txt.j2"),
+    ),
+    (
+        "en",
+        TemplateChannel::EmailBody,
+        include_str!("../../../../templates/notifications/en/email_body.md.j2"),
+    ),
+];
+
+/// File holding the embed labels of a locale.
+const EMBED_LABELS_FILE: &str = "embed.json";
+
+const BUILTIN_EMBED_LABELS: &[(&str, &str)] = &[
+    (
+        "fr",
+        include_str!("../../../../templates/notifications/fr/embed.json"),
+    ),
+    (
+        "en",
+        include_str!("../../../../templates/notifications/en/embed.json"),
+    ),
+];
+
+/// Text rendered through a template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateChannel {
+    /// Plain Discord message, sent when an embed is rejected.
+    Discord,
+    EmailSubject,
+    /// Markdown-lite email body, also turned into the HTML alternative.
+    EmailBody,
+}
+
+impl TemplateChannel {
+    pub const ALL: [TemplateChannel; 3] = [
+        TemplateChannel::Discord,
+        TemplateChannel::EmailSubject,
+        TemplateChannel::EmailBody,
+    ];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            TemplateChannel::Discord => "discord.md.j2",
+            TemplateChannel::EmailSubject => "email_subject.txt.j2",
+            TemplateChannel::EmailBody => "email_body.md.j2",
+        }
+    }
+}
+
+/// Labels of the Discord embed in one locale.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EmbedLabels {
+    title: String,
+    duplicate: String,
+    duplicate_of: String,
+    name: String,
+    phone: String,
+    email: String,
+    message: String,
+    message_continued: String,
+    unknown_ip: String,
+    categories: CategoryLabels,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CategoryLabels {
+    project: String,
+    quote: String,
+    partnership: String,
+    press: String,
+    other: String,
+}
+
+impl EmbedLabels {
+    fn parse(source: &str) -> anyhow::Result<Self> {
+        let labels: Self = serde_json::from_str(source)?;
+        // Discord rejects embeds with an empty field name or title
+        let categories = &labels.categories;
+        let empty = [
+            &labels.title,
+            &labels.duplicate,
+            &labels.duplicate_of,
+            &labels.name,
+            &labels.phone,
+            &labels.email,
+            &labels.message,
+            &labels.message_continued,
+            &labels.unknown_ip,
+            &categories.project,
+            &categories.quote,
+            &categories.partnership,
+            &categories.press,
+            &categories.other,
+        ]
+        .iter()
+        .any(|label| label.trim().is_empty());
+        if empty {
+            anyhow::bail!("labels must not be empty");
+        }
+        Ok(labels)
+    }
+
+    fn category(&self, category: ContactCategory) -> &str {
+        match category {
+            ContactCategory::Project => &self.categories.project,
+            ContactCategory::Quote => &self.categories.quote,
+            ContactCategory::Partnership => &self.categories.partnership,
+            ContactCategory::Press => &self.categories.press,
+            ContactCategory::Other => &self.categories.other,
+        }
+    }
+}
+
+/// Compiled notification templates, shared by every notifier.
+pub struct NotificationTemplates {
+    env: Environment<'static>,
+    embed_labels: BTreeMap<String, EmbedLabels>,
+    locales: BTreeSet<String>,
+    locale: String,
+}
+
+impl NotificationTemplates {
+    /// Compiled-in templates only, rendering in [`DEFAULT_LOCALE`].
+    pub fn builtin() -> Self {
+        Self::load(None, DEFAULT_LOCALE).expect("builtin templates are valid")
+    }
+
+    /// Loads the compiled-in templates, overridden by the files found in
+    /// `dir/{locale}/`, and validates all of them.
+    pub fn load(dir: Option<&Path>, locale: &str) -> anyhow::Result<Self> {
+        let mut sources = BTreeMap::new();
+        for (builtin_locale, channel, source) in BUILTIN {
+            sources.insert((builtin_locale.to_string(), *channel), source.to_string());
+        }
+        let mut label_sources = BTreeMap::new();
+        for (builtin_locale, source) in BUILTIN_EMBED_LABELS {
+            label_sources.insert(builtin_locale.to_string(), source.to_string());
+        }
+        if let Some(dir) = dir {
+            for entry in std::fs::read_dir(dir)
+                .map_err(|e| anyhow::anyhow!("Cannot read templates dir {:?}: {}", dir, e))?
+            {
+                let entry = entry?;
+                if !entry.file_type()?.is_dir() {
+                    continue;
+                }
+                let dir_locale = entry.file_name().to_string_lossy().to_string();
+                for channel in TemplateChannel::ALL {
+                    let path = entry.path().join(channel.file_name());
+                    if path.is_file() {
+                        sources.insert(
+                            (dir_locale.clone(), channel),
+                            std::fs::read_to_string(path)?,
+                        );
+                    }
+                }
+                let path = entry.path().join(EMBED_LABELS_FILE);
+                if path.is_file() {
+                    label_sources.insert(dir_locale, std::fs::read_to_string(path)?);
+                }
+            }
+        }
+
+        let mut env = Environment::new();
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+        let mut locales = BTreeSet::new();
+        for ((template_locale, channel), source) in sources {
+            let name = template_name(&template_locale, channel);
+            env.add_template_owned(name.clone(), source)
+                .map_err(|e| anyhow::anyhow!("Invalid template {}: {}", name, e))?;
+            locales.insert(template_locale);
+        }
+        let mut embed_labels = BTreeMap::new();
+        for (labels_locale, source) in label_sources {
+            let labels = EmbedLabels::parse(&source).map_err(|e| {
+                anyhow::anyhow!(
+                    "Invalid embed labels {}/{}: {}",
+                    labels_locale,
+                    EMBED_LABELS_FILE,
+                    e
+                )
+            })?;
+            embed_labels.insert(labels_locale.clone(), labels);
+            locales.insert(labels_locale);
+        }
+
+        if !locales.contains(locale) {
+            anyhow::bail!(
+                "NOTIFICATION_LOCALE '{}' has no templates, available: {}",
+                locale,
+                locales.iter().cloned().collect::<Vec<_>>().join(", ")
+            );
+        }
+
+        let templates = Self {
+            env,
+            embed_labels,
+            locales,
+            locale: locale.to_string(),
+        };
+        templates.validate()?;
+        Ok(templates)
+    }
+
+    /// Renders every template against [`sample_event`] so missing variables
+    /// and runtime errors show up at startup.
+    fn validate(&self) -> anyhow::Result<()> {
+        let errors = self
+            .env
+            .templates()
+            .filter_map(|(name, template)| {
+                template
+                    .render(event_context(&sample_event()))
+                    .err()
+                    .map(|e| format!("{}: {:#}", name, e))
+            })
+            .collect::<Vec<_>>();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!("Invalid notification templates: {}", errors.join("; "))
+        }
+    }
+
+    /// Locale used by [`Self::render`].
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Renders `channel` in the configured locale.
+    pub fn render(&self, channel: TemplateChannel, event: &NotificationEvent) -> AppResult<String> {
+        self.render_in(&self.locale, channel, event)
+            .map_err(AppError::Notification)
+    }
+
+    /// Renders `channel` in `locale`, falling back to [`DEFAULT_LOCALE`]
+    /// when that locale only overrides some channels.
+    pub fn render_in(
+        &self,
+        locale: &str,
+        channel: TemplateChannel,
+        event: &NotificationEvent,
+    ) -> Result<String, String> {
+        if !self.locales.contains(locale) {
+            return Err(format!("unknown locale '{}'", locale));
+        }
+        let template = self
+            .env
+            .get_template(&template_name(locale, channel))
+            .or_else(|_| {
+                self.env
+                    .get_template(&template_name(DEFAULT_LOCALE, channel))
+            })
+            .map_err(|e| e.to_string())?;
+        template
+            .render(event_context(event))
+            .map_err(|e| format!("{:#}", e))
+    }
+
+    /// Renders an arbitrary template source against [`sample_event`], for
+    /// trying a change before deploying it.
+    pub fn render_source(&self, source: &str) -> Result<String, String> {
+        self.env
+            .render_str(source, event_context(&sample_event()))
+            .map_err(|e| format!("{:#}", e))
+    }
+
+    /// Discord embed listing the event fields, easier to scan on mobile than
+    /// plain text, labelled in the configured locale.
+    ///
+    /// Expects a sanitized event, see [`sanitize::sanitize_event`].
+    pub fn discord_embed(&self, event: &NotificationEvent) -> Value {
+        let labels = self
+            .embed_labels
+            .get(&self.locale)
+            .or_else(|| self.embed_labels.get(DEFAULT_LOCALE))
+            .expect("builtin embed labels are loaded");
+        match event {
+            NotificationEvent::ContactSubmitted(contact) => contact_embed(labels, contact),
+        }
+    }
+
+    /// Email rendered in the configured locale.
+    pub fn email(&self, event: &NotificationEvent) -> AppResult<EmailContent> {
+        let body = self.render(TemplateChannel::EmailBody, event)?;
+        Ok(EmailContent {
+            subject: self.render(TemplateChannel::EmailSubject, event)?,
+            text: strip_bold(&body),
+            html: html(&body),
+        })
+    }
+}
+
+fn template_name(locale: &str, channel: TemplateChannel) -> String {
+    format!("{}/{}", locale, channel.file_name())
+}
+
+/// Variables available to the templates.
+fn event_context(event: &NotificationEvent) -> minijinja::Value {
+    let metadata = event.metadata();
+    let common = context! {
+        event => event.kind(),
+        request_id => metadata.request_id,
+        client_ip => metadata.client_ip.map(|ip| ip.to_string()),
+        occurred_at => metadata.occurred_at.to_rfc3339(),
+    };
+
+    match event {
+        NotificationEvent::ContactSubmitted(contact) => context! {
+            category => contact.category,
+            category_label => contact.category.label(),
+            first_name => contact.first_name,
+            last_name => contact.last_name,
+            phone => contact.phone,
+            email => contact.email,
+            message => contact.message,
+            duplicate_of => contact.duplicate_of,
+            ..common
+        },
+    }
+}
+
+/// Data used for startup validation and admin previews.
+pub fn sample_event() -> NotificationEvent {
+    NotificationEvent::ContactSubmitted(ContactSubmitted {
+        metadata: EventMetadata::new("preview".to_string(), Some([203, 0, 113, 7].into())),
+        category: ContactCategory::Project,
+        first_name: "Jeanne".to_string(),
+        last_name: "Dupont".to_string(),
+        phone: "+33612345678".to_string(),
+        email: "jeanne.dupont@example.com".to_string(),
+        message: "Bonjour, j'aimerais discuter d'un projet vidéo.".to_string(),
+        duplicate_of: None,
+    })
+}
+
+fn colour(category: ContactCategory) -> u32 {
+    match category {
+        ContactCategory::Project => 0x5865F2,
+        ContactCategory::Quote => 0x57F287,
+        ContactCategory::Partnership => 0xFEE75C,
+        ContactCategory::Press => 0xEB459E,
+        ContactCategory::Other => 0x99AAB5,
+    }
+}
+
+fn contact_embed(labels: &EmbedLabels, contact: &ContactSubmitted) -> Value {
+    let mut fields = Vec::new();
+    if let Some(original) = &contact.duplicate_of {
+        fields.push(json!({ "name": labels.duplicate_of, "value": original, "inline": false }));
+    }
+    fields.extend([
+        json!({
+            "name": labels.name,
+            "value": format!("{} {}", contact.first_name, contact.last_name),
+            "inline": true
+        }),
+        json!({ "name": labels.phone, "value": contact.phone, "inline": true }),
+        json!({ "name": labels.email, "value": contact.email, "inline": false }),
+    ]);
+    // Escaping can push the message past the field limit
+    for (i, part) in sanitize::split_content(&contact.message, FIELD_LIMIT)
+        .into_iter()
+        .enumerate()
+    {
+        let name = if i == 0 {
+            &labels.message
+        } else {
+            &labels.message_continued
+        };
+        fields.push(json!({ "name": name, "value": part, "inline": false }));
+    }
+
+    let client_ip = contact
+        .metadata
+        .client_ip
+        .map(|ip| ip.to_string())
+        .unwrap_or_else(|| labels.unknown_ip.clone());
+    let footer = format!(
+        "Request ID: {} · IP: {}",
+        contact.metadata.request_id, client_ip
+    );
+
+    let title = format!("{} · {}", labels.title, labels.category(contact.category));
+    json!({
+        "title": match contact.duplicate_of {
+            Some(_) => format!("⚠️ {} · {}", labels.duplicate, title),
+            None => title,
+        },
+        "color": colour(contact.category),
+        "timestamp": contact.metadata.occurred_at.to_rfc3339(),
+        "fields": fields,
+        "footer": { "text": footer }
+    })
+}
+
+/// Rendered email, sent as a plain text and HTML alternative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailContent {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Drops the bold markers from the email body.
+fn strip_bold(message: &str) -> String {
+    message.replace("**", "")
+}
+
+/// Escapes the email body and turns bold markers and line breaks into
+/// their HTML equivalents.
+fn html(message: &str) -> String {
+    let escaped = message
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+
+    let mut html = String::with_capacity(escaped.len());
+    for (i, part) in escaped.split("**").enumerate() {
+        if i > 0 {
+            html.push_str(if i % 2 == 1 { "<strong>" } else { "</strong>" });
+        }
+        html.push_str(part);
+    }
+    if escaped.matches("**").count() % 2 == 1 {
+        html.push_str("</strong>");
+    }
+
+    format!(
+        "<!DOCTYPE html><html><body><p>{}</p></body></html>",
+        html.replace('\n', "<br>\n")
+    )
+}
+
+/// JSON body posted to generic webhook consumers.
+pub fn webhook(event: &NotificationEvent, delivery_id: &str) -> Value {
+    let data = match event {
+        NotificationEvent::ContactSubmitted(contact) => json!(contact),
+    };
+
+    json!({
+        "id": delivery_id,
+        "event": event.kind(),
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "data": data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discord_text_lists_fields() {
+        let text = NotificationTemplates::builtin()
+            .render(TemplateChannel::Discord, &NotificationEvent::sample())
+            .unwrap();
+        assert!(text.starts_with("**Yo brozer, nouvelle demande de contact!**"));
+        assert!(text.contains("John"));
+        assert!(text.contains("Doe"));
+        assert!(text.contains("john@example.com"));
+        assert!(text.contains("Projet"));
+    }
+
+    #[test]
+    fn test_english_templates() {
+        let templates = NotificationTemplates::load(None, "en").unwrap();
+        let text = templates
+            .render(TemplateChannel::Discord, &NotificationEvent::sample())
+            .unwrap();
+        assert!(text.contains("Category: Project"));
+
+        let email = templates.email(&NotificationEvent::sample()).unwrap();
+        assert_eq!(email.subject, "New contact request · John Doe");
+    }
+
+    fn temp_dir() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("templates-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_overrides_from_dir_with_fallback() {
+        let dir = temp_dir();
+        std::fs::create_dir(dir.join("de")).unwrap();
+        std::fs::write(
+            dir.join("de").join("email_subject.txt.j2"),
+            "Neue Anfrage von {{ first_name }}",
+        )
+        .unwrap();
+
+        let templates = NotificationTemplates::load(Some(&dir), "de").unwrap();
+        let email = templates.email(&NotificationEvent::sample()).unwrap();
+        assert_eq!(email.subject, "Neue Anfrage von John");
+        // Channels missing from the locale fall back to the French ones
+        assert!(email.text.contains("Nouvelle demande de contact"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_variable_fails_validation() {
+        let dir = temp_dir();
+        std::fs::create_dir(dir.join("fr")).unwrap();
+        std::fs::write(dir.join("fr").join("discord.md.j2"), "{{ frist_name }}").unwrap();
+
+        let error = NotificationTemplates::load(Some(&dir), "fr")
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("fr/discord.md.j2"), "{}", error);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_locale_is_rejected() {
+        assert!(NotificationTemplates::load(None, "xx").is_err());
+    }
+
+    #[test]
+    fn test_embed_fields_and_footer() {
+        let embed = NotificationTemplates::builtin().discord_embed(&NotificationEvent::sample());
+
+        assert_eq!(embed["color"], 0x5865F2);
+        assert_eq!(embed["fields"][0]["value"], "John Doe");
+        assert_eq!(embed["fields"][2]["value"], "john@example.com");
+        assert_eq!(embed["fields"][3]["value"], "hello");
+        assert_eq!(
+            embed["footer"]["text"],
+            "Request ID: req-1 · IP: 203.0.113.7"
+        );
+        assert!(embed["timestamp"].is_string());
+    }
+
+    #[test]
+    fn test_embed_labels_follow_the_locale() {
+        let event = NotificationEvent::ContactSubmitted(ContactSubmitted {
+            duplicate_of: Some("req-0".to_string()),
+            ..ContactSubmitted::sample()
+        });
+        let embed = NotificationTemplates::load(None, "en")
+            .unwrap()
+            .discord_embed(&event);
+
+        assert_eq!(
+            embed["title"],
+            "⚠️ Duplicate · New contact request · Project"
+        );
+        assert_eq!(embed["fields"][0]["name"], "Duplicate of");
+        assert_eq!(embed["fields"][1]["name"], "Name");
+        assert_eq!(embed["fields"][2]["name"], "Phone");
+    }
+
+    #[test]
+    fn test_locale_without_embed_labels_falls_back() {
+        let dir = temp_dir();
+        std::fs::create_dir(dir.join("de")).unwrap();
+        std::fs::write(dir.join("de").join("email_subject.txt.j2"), "Anfrage").unwrap();
+
+        let embed = NotificationTemplates::load(Some(&dir), "de")
+            .unwrap()
+            .discord_embed(&NotificationEvent::sample());
+        assert_eq!(embed["fields"][0]["name"], "Nom");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_incomplete_embed_labels_fail_validation() {
+        let dir = temp_dir();
+        std::fs::create_dir(dir.join("de")).unwrap();
+        std::fs::write(
+            dir.join("de").join(EMBED_LABELS_FILE),
+            r#"{ "title": "Neue Kontaktanfrage" }"#,
+        )
+        .unwrap();
+
+        let error = NotificationTemplates::load(Some(&dir), "de")
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("de/embed.json"), "{}", error);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_long_message_spans_several_fields() {
+        let event = NotificationEvent::ContactSubmitted(ContactSubmitted {
+            message: "*".repeat(1000),
+            ..ContactSubmitted::sample()
+        });
+        let embed =
+            NotificationTemplates::builtin().discord_embed(&sanitize::sanitize_event(&event));
+
+        assert_eq!(embed["fields"][3]["name"], "Message");
+        assert_eq!(embed["fields"][4]["name"], "Message (suite)");
+    }
+
+    #[test]
+    fn test_duplicates_are_marked() {
+        let event = NotificationEvent::ContactSubmitted(ContactSubmitted {
+            duplicate_of: Some("req-0".to_string()),
+            ..ContactSubmitted::sample()
+        });
+
+        let text = NotificationTemplates::builtin()
+            .render(TemplateChannel::Discord, &event)
+            .unwrap();
+        assert!(text.starts_with("⚠️ **Doublon de la demande req-0**\n**Yo brozer"));
+
+        let embed = NotificationTemplates::builtin().discord_embed(&event);
+        assert!(embed["title"].as_str().unwrap().starts_with("⚠️ Doublon"));
+        assert_eq!(embed["fields"][0]["value"], "req-0");
+
+        let email = NotificationTemplates::load(None, "en")
+            .unwrap()
+            .email(&event)
+            .unwrap();
+        assert!(email.subject.starts_with("[Duplicate] New contact request"));
+    }
+
+    #[test]
+    fn test_html_escapes_and_formats() {
+        let html = html("**Email:** <a@b.c>\nhello");
+        assert!(html.contains("<strong>Email:</strong> &lt;a@b.c&gt;<br>"));
+    }
+
+    #[test]
+    fn test_html_closes_unbalanced_bold() {
+        let html = html("**oops");
+        assert!(html.contains("<strong>oops</strong>"));
+    }
+
+    #[test]
+    fn test_email_subject_and_text() {
+        let email = NotificationTemplates::builtin()
+            .email(&NotificationEvent::sample())
+            .unwrap();
+        assert_eq!(email.subject, "Nouvelle demande de contact · John Doe");
+        assert!(email.text.contains("Email: john@example.com"));
+        assert!(email.text.contains("IP: 203.0.113.7"));
+        assert!(!email.text.contains("**"));
+        assert!(email.html.contains("<strong>Email:</strong>"));
+    }
+
+    #[test]
+    fn test_webhook_body() {
+        let body = webhook(&NotificationEvent::sample(), "delivery-1");
+        assert_eq!(body["id"], "delivery-1");
+        assert_eq!(body["event"], "contact.submitted");
+        assert_eq!(body["data"]["metadata"]["request_id"], "req-1");
+        assert_eq!(body["data"]["message"], "hello");
+    }
+}
@@ -0,0 +1,320 @@
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::Sha256;
+use std::time::Duration;
+
+use super::{Notification, NotificationEvent, templates};
+use crate::common::errors::{AppError, AppResult};
+
+pub const SIGNATURE_HEADER: &str = "X-Utazon-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Utazon-Timestamp";
+pub const DELIVERY_HEADER: &str = "X-Utazon-Delivery";
+
+#[derive(Clone)]
+pub struct WebhookEndpoint {
+    pub url: String,
+    pub timeout: Duration,
+}
+
+#[derive(Clone)]
+pub struct WebhookConfig {
+    pub endpoints: Vec<WebhookEndpoint>,
+    pub secret: String,
+    pub max_retries: u32,
+    pub retry_base_delay: Duration,
+}
+
+/// Posts notifications as signed JSON to generic webhook consumers (n8n, CRM...).
+///
+/// Each request carries an HMAC-SHA256 of `"{timestamp}.{body}"` in
+/// `X-Utazon-Signature` so receivers can authenticate it and reject replays.
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    config: WebhookConfig,
+}
+
+enum Attempt {
+    Delivered,
+    Retryable(String),
+    Fatal(String),
+}
+
+impl WebhookNotifier {
+    pub fn new(client: reqwest::Client, config: WebhookConfig) -> Self {
+        Self { client, config }
+    }
+
+    fn signature(&self, timestamp: i64, body: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.config.secret.as_bytes())
+            .expect("HMAC accepts any key size");
+        mac.update(format!("{}.{}", timestamp, body).as_bytes());
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    async fn attempt(&self, endpoint: &WebhookEndpoint, delivery_id: &str, body: &str) -> Attempt {
+        // Signed per attempt so retries don't look like replays to the receiver
+        let timestamp = chrono::Utc::now().timestamp();
+
+        let response = self
+            .client
+            .post(&endpoint.url)
+            .timeout(endpoint.timeout)
+            .header("Content-Type", "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, self.signature(timestamp, body))
+            .header(DELIVERY_HEADER, delivery_id)
+            .body(body.to_string())
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => Attempt::Delivered,
+            Ok(response)
+                if response.status().is_server_error()
+                    || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS =>
+            {
+                Attempt::Retryable(format!("HTTP {}", response.status()))
+            }
+            Ok(response) => Attempt::Fatal(format!("HTTP {}", response.status())),
+            Err(e) => Attempt::Retryable(e.to_string()),
+        }
+    }
+
+    #[tracing::instrument(skip(self, endpoint, body), fields(url = %endpoint.url))]
+    async fn deliver(
+        &self,
+        endpoint: &WebhookEndpoint,
+        delivery_id: &str,
+        body: &str,
+    ) -> AppResult<()> {
+        let mut attempt = 0;
+
+        loop {
+            let error = match self.attempt(endpoint, delivery_id, body).await {
+                Attempt::Delivered => {
+                    tracing::info!("Delivered webhook notification");
+                    return Ok(());
+                }
+                Attempt::Fatal(error) => return Err(AppError::Webhook(error)),
+                Attempt::Retryable(error) => error,
+            };
+
+            if attempt >= self.config.max_retries {
+                return Err(AppError::Webhook(format!(
+                    "giving up after {} attempts: {}",
+                    attempt + 1,
+                    error
+                )));
+            }
+
+            let delay = backoff(self.config.retry_base_delay, attempt);
+            tracing::warn!(
+                attempt = attempt + 1,
+                delay_ms = delay.as_millis() as u64,
+                "Webhook delivery failed, retrying: {}",
+                error
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Exponential backoff with up to 50% random jitter.
+fn backoff(base: Duration, attempt: u32) -> Duration {
+    let delay = base.saturating_mul(2u32.saturating_pow(attempt));
+    delay + delay.mul_f64(rand::rng().random_range(0.0..0.5))
+}
+
+#[async_trait]
+impl Notification for WebhookNotifier {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    #[tracing::instrument(skip(self, event), fields(request_id = %event.request_id()))]
+    async fn notify(&self, event: &NotificationEvent) -> AppResult<()> {
+        let delivery_id = uuid::Uuid::new_v4().to_string();
+        let body = templates::webhook(event, &delivery_id).to_string();
+
+        let results = join_all(
+            self.config
+                .endpoints
+                .iter()
+                .map(|endpoint| self.deliver(endpoint, &delivery_id, &body)),
+        )
+        .await;
+
+        let errors = results
+            .iter()
+            .zip(&self.config.endpoints)
+            .filter_map(|(result, endpoint)| {
+                result
+                    .as_ref()
+                    .err()
+                    .map(|e| format!("{}: {}", endpoint.url, e))
+            })
+            .collect::<Vec<_>>();
+
+        if errors.len() == results.len() {
+            Err(AppError::Webhook(format!(
+                "All webhook deliveries failed: {}",
+                errors.join("; ")
+            )))
+        } else {
+            if !errors.is_empty() {
+                tracing::warn!(
+                    "Partial webhook failure: {}/{} succeeded: {}",
+                    results.len() - errors.len(),
+                    results.len(),
+                    errors.join("; ")
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        Router, body::Bytes, extract::State, http::HeaderMap, http::StatusCode, routing::post,
+    };
+    use std::sync::{
+        Arc, Mutex,
+        atomic::{AtomicU32, Ordering},
+    };
+
+    #[derive(Clone, Default)]
+    struct Receiver {
+        failures_before_success: u32,
+        calls: Arc<AtomicU32>,
+        received: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+    }
+
+    async fn receive(
+        State(receiver): State<Receiver>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> StatusCode {
+        let call = receiver.calls.fetch_add(1, Ordering::SeqCst);
+        if call < receiver.failures_before_success {
+            return StatusCode::SERVICE_UNAVAILABLE;
+        }
+        receiver
+            .received
+            .lock()
+            .unwrap()
+            .push((headers, String::from_utf8(body.to_vec()).unwrap()));
+        StatusCode::NO_CONTENT
+    }
+
+    async fn serve(receiver: Receiver) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(receiver);
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/hook", addr)
+    }
+
+    fn notifier(urls: Vec<String>, max_retries: u32) -> WebhookNotifier {
+        WebhookNotifier::new(
+            reqwest::Client::new(),
+            WebhookConfig {
+                endpoints: urls
+                    .into_iter()
+                    .map(|url| WebhookEndpoint {
+                        url,
+                        timeout: Duration::from_secs(2),
+                    })
+                    .collect(),
+                secret: "webhook-secret".to_string(),
+                max_retries,
+                retry_base_delay: Duration::from_millis(1),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_payload_is_signed() {
+        let receiver = Receiver::default();
+        let url = serve(receiver.clone()).await;
+        let notifier = notifier(vec![url], 0);
+
+        notifier.notify(&NotificationEvent::sample()).await.unwrap();
+
+        let received = receiver.received.lock().unwrap();
+        let (headers, body) = &received[0];
+        let timestamp: i64 = headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+        assert_eq!(
+            headers[SIGNATURE_HEADER].to_str().unwrap(),
+            notifier.signature(timestamp, body)
+        );
+
+        let json: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(json["event"], "contact.submitted");
+        assert_eq!(json["data"]["message"], "hello");
+        assert_eq!(json["data"]["metadata"]["request_id"], "req-1");
+        assert_eq!(json["id"], headers[DELIVERY_HEADER].to_str().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let receiver = Receiver {
+            failures_before_success: 2,
+            ..Receiver::default()
+        };
+        let url = serve(receiver.clone()).await;
+
+        notifier(vec![url], 3)
+            .notify(&NotificationEvent::sample())
+            .await
+            .unwrap();
+
+        assert_eq!(receiver.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(receiver.received.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let receiver = Receiver {
+            failures_before_success: 10,
+            ..Receiver::default()
+        };
+        let url = serve(receiver.clone()).await;
+
+        let result = notifier(vec![url], 2)
+            .notify(&NotificationEvent::sample())
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(receiver.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_partial_failure_is_tolerated() {
+        let receiver = Receiver::default();
+        let url = serve(receiver.clone()).await;
+        let unreachable = "http://127.0.0.1:1/hook".to_string();
+
+        notifier(vec![url, unreachable], 0)
+            .notify(&NotificationEvent::sample())
+            .await
+            .unwrap();
+
+        assert_eq!(receiver.received.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_backoff_grows_exponentially() {
+        let base = Duration::from_millis(100);
+        assert!(backoff(base, 0) >= Duration::from_millis(100));
+        assert!(backoff(base, 0) < Duration::from_millis(150));
+        assert!(backoff(base, 3) >= Duration::from_millis(800));
+    }
+}
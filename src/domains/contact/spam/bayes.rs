@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Below this many training messages in either class, the classifier has no
+/// opinion.
+pub const MIN_TRAINING_MESSAGES: u32 = 5;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ClassCounts {
+    messages: u32,
+    tokens: u64,
+    words: HashMap<String, u32>,
+}
+
+impl ClassCounts {
+    fn add(&mut self, tokens: &[String]) {
+        self.messages += 1;
+        self.tokens += tokens.len() as u64;
+        for token in tokens {
+            *self.words.entry(token.clone()).or_default() += 1;
+        }
+    }
+
+    /// Log-likelihood of the tokens with Laplace smoothing.
+    fn log_likelihood(&self, tokens: &[String], vocabulary: usize) -> f64 {
+        let denominator = (self.tokens + vocabulary as u64) as f64;
+        tokens
+            .iter()
+            .map(|token| {
+                let count = self.words.get(token).copied().unwrap_or(0);
+                ((count + 1) as f64 / denominator).ln()
+            })
+            .sum()
+    }
+}
+
+/// Multinomial naive Bayes over the words of a message, trained from the
+/// admin quarantine decisions and saved as JSON.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BayesClassifier {
+    spam: ClassCounts,
+    ham: ClassCounts,
+}
+
+impl BayesClassifier {
+    pub fn train(&mut self, text: &str, spam: bool) {
+        let tokens = tokenize(text);
+        if spam {
+            self.spam.add(&tokens);
+        } else {
+            self.ham.add(&tokens);
+        }
+    }
+
+    /// Number of spam and ham training messages.
+    pub fn trained(&self) -> (u32, u32) {
+        (self.spam.messages, self.ham.messages)
+    }
+
+    /// Probability from 0 to 1 that `text` is spam, `None` until enough of
+    /// both classes were seen.
+    pub fn spam_probability(&self, text: &str) -> Option<f64> {
+        if self.spam.messages < MIN_TRAINING_MESSAGES || self.ham.messages < MIN_TRAINING_MESSAGES {
+            return None;
+        }
+
+        let tokens = tokenize(text);
+        let vocabulary = self
+            .spam
+            .words
+            .keys()
+            .chain(
+                self.ham
+                    .words
+                    .keys()
+                    .filter(|w| !self.spam.words.contains_key(*w)),
+            )
+            .count();
+        let total = (self.spam.messages + self.ham.messages) as f64;
+
+        let spam = (self.spam.messages as f64 / total).ln()
+            + self.spam.log_likelihood(&tokens, vocabulary);
+        let ham =
+            (self.ham.messages as f64 / total).ln() + self.ham.log_likelihood(&tokens, vocabulary);
+        Some(1.0 / (1.0 + (ham - spam).exp()))
+    }
+}
+
+/// Lowercase words of 2 to 30 characters.
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| (2..=30).contains(&word.chars().count()))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trained() -> BayesClassifier {
+        let mut classifier = BayesClassifier::default();
+        for text in [
+            "Boost your SEO ranking with our backlinks package",
+            "Cheap backlinks and guest posts for your website ranking",
+            "Invest in crypto today, guaranteed returns on bitcoin",
+            "We sell SEO services, first page of Google guaranteed",
+            "Bitcoin trading bot with guaranteed daily profits",
+        ] {
+            classifier.train(text, true);
+        }
+        for text in [
+            "Bonjour, nous cherchons un vidéaste pour notre mariage en juin",
+            "Hello, we need a promotional video for our restaurant opening",
+            "Could you send a quote for filming our company event?",
+            "Nous aimerions un clip pour la sortie de notre album",
+            "Is drone footage included in your wedding video package?",
+        ] {
+            classifier.train(text, false);
+        }
+        classifier
+    }
+
+    #[test]
+    fn test_untrained_classifier_has_no_opinion() {
+        let mut classifier = BayesClassifier::default();
+        classifier.train("buy backlinks", true);
+
+        assert_eq!(classifier.spam_probability("buy backlinks"), None);
+    }
+
+    #[test]
+    fn test_classifies_like_its_training() {
+        let classifier = trained();
+
+        let spam = classifier
+            .spam_probability("Guaranteed SEO backlinks for your ranking")
+            .unwrap();
+        let ham = classifier
+            .spam_probability("We would like a video for our wedding in June")
+            .unwrap();
+
+        assert!(spam > 0.8, "spam scored {spam}");
+        assert!(ham < 0.2, "ham scored {ham}");
+    }
+
+    #[test]
+    fn test_model_round_trips_as_json() {
+        let json = serde_json::to_string(&trained()).unwrap();
+        let classifier: BayesClassifier = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(classifier.trained(), (5, 5));
+    }
+}
@@ -0,0 +1,257 @@
+mod bayes;
+mod rules;
+
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Mutex};
+
+pub use bayes::{BayesClassifier, MIN_TRAINING_MESSAGES};
+pub use rules::Script;
+
+use super::service::ContactSubmitted;
+use crate::common::errors::{AppError, AppResult};
+
+/// Points added by a classifier certain a message is spam, scaled by its
+/// probability.
+const CLASSIFIER_POINTS: f64 = 4.0;
+
+#[derive(Debug, Clone)]
+pub struct SpamConfig {
+    /// Submissions scoring at least this much are quarantined instead of
+    /// notified.
+    pub quarantine_threshold: f64,
+    /// Links tolerated before each new one adds a point.
+    pub max_links: usize,
+    /// Words or phrases matched on whole words, case insensitively.
+    pub blocked_words: Vec<String>,
+    /// Matched against links and the sender's email, subdomains included.
+    pub blocked_domains: Vec<String>,
+    /// Scripts visitors are expected to write in.
+    pub scripts: Vec<Script>,
+    /// Where the classifier is saved after training, in memory only when unset.
+    pub model_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpamRule {
+    Links,
+    BlockedWord,
+    BlockedDomain,
+    AllCaps,
+    ScriptMismatch,
+    Classifier,
+}
+
+impl SpamRule {
+    /// Metric label.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SpamRule::Links => "links",
+            SpamRule::BlockedWord => "blocked_word",
+            SpamRule::BlockedDomain => "blocked_domain",
+            SpamRule::AllCaps => "all_caps",
+            SpamRule::ScriptMismatch => "script_mismatch",
+            SpamRule::Classifier => "classifier",
+        }
+    }
+}
+
+/// A rule that fired, with the points it added.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpamSignal {
+    pub rule: SpamRule,
+    pub points: f64,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpamVerdict {
+    pub score: f64,
+    pub signals: Vec<SpamSignal>,
+}
+
+/// Scores contact messages on the rules and the naive Bayes classifier,
+/// catching the spam written by humans that the bot checks let through.
+pub struct SpamFilter {
+    config: SpamConfig,
+    classifier: Mutex<BayesClassifier>,
+    save_lock: tokio::sync::Mutex<()>,
+}
+
+impl SpamFilter {
+    /// Loads the classifier from `model_path` when it was saved before.
+    pub fn new(config: SpamConfig) -> anyhow::Result<Self> {
+        let classifier = match &config.model_path {
+            Some(path) if path.exists() => serde_json::from_slice(&std::fs::read(path)?)
+                .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?,
+            _ => BayesClassifier::default(),
+        };
+        Ok(Self {
+            config,
+            classifier: Mutex::new(classifier),
+            save_lock: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    pub fn config(&self) -> &SpamConfig {
+        &self.config
+    }
+
+    pub fn score(&self, contact: &ContactSubmitted) -> SpamVerdict {
+        let text = training_text(contact);
+        let hosts = rules::link_hosts(&contact.message);
+
+        let mut signals = [
+            rules::links(&self.config, &hosts),
+            rules::blocked_words(&self.config, &text),
+            rules::blocked_domains(&self.config, &hosts, &contact.email),
+            rules::all_caps(&contact.message),
+            rules::script_mismatch(&self.config, &contact.message),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        let probability = self
+            .classifier
+            .lock()
+            .expect("spam classifier lock poisoned")
+            .spam_probability(&text);
+        if let Some(probability) = probability.filter(|&p| p >= 0.5) {
+            signals.push(SpamSignal {
+                rule: SpamRule::Classifier,
+                points: probability * CLASSIFIER_POINTS,
+                detail: format!("{:.0}% spam", probability * 100.0),
+            });
+        }
+
+        SpamVerdict {
+            score: signals.iter().map(|signal| signal.points).sum(),
+            signals,
+        }
+    }
+
+    pub fn is_spam(&self, verdict: &SpamVerdict) -> bool {
+        verdict.score >= self.config.quarantine_threshold
+    }
+
+    /// Learns from an admin decision and saves the model.
+    pub async fn train(&self, text: &str, spam: bool) -> AppResult<()> {
+        // Held from the snapshot to the rename so an older model is never saved last
+        let _guard = self.save_lock.lock().await;
+        let model = {
+            let mut classifier = self
+                .classifier
+                .lock()
+                .expect("spam classifier lock poisoned");
+            classifier.train(text, spam);
+            serde_json::to_vec(&*classifier)
+                .map_err(|e| AppError::Database(format!("failed to serialize spam model: {}", e)))?
+        };
+
+        let Some(path) = &self.config.model_path else {
+            return Ok(());
+        };
+        // Written aside then renamed, a crash mid-write keeps the previous model
+        let tmp = path.with_extension("tmp");
+        let result = match tokio::fs::write(&tmp, model).await {
+            Ok(()) => tokio::fs::rename(&tmp, path).await,
+            Err(e) => Err(e),
+        };
+        result.map_err(|e| AppError::Database(format!("failed to save spam model: {}", e)))
+    }
+
+    /// Number of spam and ham messages the classifier learnt from.
+    pub fn trained(&self) -> (u32, u32) {
+        self.classifier
+            .lock()
+            .expect("spam classifier lock poisoned")
+            .trained()
+    }
+}
+
+/// Text the classifier sees. Names are included, spammers often put their
+/// pitch there.
+pub fn training_text(contact: &ContactSubmitted) -> String {
+    format!(
+        "{} {} {}",
+        contact.first_name, contact.last_name, contact.message
+    )
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    pub(crate) fn config() -> SpamConfig {
+        SpamConfig {
+            quarantine_threshold: 5.0,
+            max_links: 2,
+            blocked_words: vec!["casino".to_string(), "backlinks".to_string()],
+            blocked_domains: vec!["spam.example".to_string()],
+            scripts: vec![Script::Latin],
+            model_path: None,
+        }
+    }
+
+    fn contact(message: &str) -> ContactSubmitted {
+        ContactSubmitted {
+            message: message.to_string(),
+            ..ContactSubmitted::sample()
+        }
+    }
+
+    #[test]
+    fn test_genuine_message_scores_zero() {
+        let filter = SpamFilter::new(config()).unwrap();
+        let verdict = filter.score(&contact(
+            "Bonjour, nous préparons un mariage en juin et cherchons un vidéaste. \
+             Vous trouverez nos idées sur https://pinterest.com/nous",
+        ));
+
+        assert_eq!(verdict.score, 0.0);
+        assert!(!filter.is_spam(&verdict));
+    }
+
+    #[test]
+    fn test_rules_add_up_to_quarantine() {
+        let filter = SpamFilter::new(config()).unwrap();
+        let verdict = filter.score(&contact(
+            "BEST CASINO BONUS AND BACKLINKS https://a.io https://b.io https://c.io",
+        ));
+
+        let rules = verdict.signals.iter().map(|s| s.rule).collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            vec![SpamRule::Links, SpamRule::BlockedWord, SpamRule::AllCaps]
+        );
+        assert_eq!(verdict.score, 1.0 + 4.0 + 2.0);
+        assert!(filter.is_spam(&verdict));
+    }
+
+    #[tokio::test]
+    async fn test_training_is_saved_and_used() {
+        let path = std::env::temp_dir().join(format!("spam-{}.json", uuid::Uuid::new_v4()));
+        let config = SpamConfig {
+            model_path: Some(path.clone()),
+            ..config()
+        };
+        let filter = SpamFilter::new(config.clone()).unwrap();
+        for i in 0..MIN_TRAINING_MESSAGES {
+            filter
+                .train(&format!("cheap crypto signals group {i}"), true)
+                .await
+                .unwrap();
+            filter
+                .train(&format!("wedding video in june {i}"), false)
+                .await
+                .unwrap();
+        }
+
+        let reloaded = SpamFilter::new(config).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reloaded.trained(), (5, 5));
+        let verdict = reloaded.score(&contact("join our crypto signals group"));
+        assert_eq!(verdict.signals[0].rule, SpamRule::Classifier);
+    }
+}
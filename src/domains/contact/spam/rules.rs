@@ -0,0 +1,279 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::{SpamConfig, SpamRule, SpamSignal};
+
+const POINTS_PER_EXTRA_LINK: f64 = 1.0;
+const POINTS_PER_BLOCKED_WORD: f64 = 2.0;
+const POINTS_BLOCKED_DOMAIN: f64 = 5.0;
+const POINTS_ALL_CAPS: f64 = 2.0;
+const POINTS_SCRIPT_MISMATCH: f64 = 3.0;
+
+/// Shorter messages are too short to be shouting or to have a script.
+const MIN_LETTERS: usize = 20;
+const ALL_CAPS_RATIO: f64 = 0.7;
+
+/// Writing systems, as far as telling a French or English visitor apart from
+/// spam written in another script goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Thai,
+    Hangul,
+    Kana,
+    Han,
+    Other,
+}
+
+impl Script {
+    pub fn of(c: char) -> Option<Script> {
+        if !c.is_alphabetic() {
+            return None;
+        }
+        Some(match c as u32 {
+            0x0041..=0x024F | 0x1E00..=0x1EFF => Script::Latin,
+            0x0370..=0x03FF | 0x1F00..=0x1FFF => Script::Greek,
+            0x0400..=0x052F => Script::Cyrillic,
+            0x0590..=0x05FF => Script::Hebrew,
+            0x0600..=0x06FF | 0x0750..=0x077F => Script::Arabic,
+            0x0900..=0x097F => Script::Devanagari,
+            0x0E00..=0x0E7F => Script::Thai,
+            0x1100..=0x11FF | 0xAC00..=0xD7AF => Script::Hangul,
+            0x3040..=0x30FF => Script::Kana,
+            0x3400..=0x4DBF | 0x4E00..=0x9FFF => Script::Han,
+            _ => Script::Other,
+        })
+    }
+}
+
+impl Script {
+    pub const ALL: [Script; 11] = [
+        Script::Latin,
+        Script::Greek,
+        Script::Cyrillic,
+        Script::Hebrew,
+        Script::Arabic,
+        Script::Devanagari,
+        Script::Thai,
+        Script::Hangul,
+        Script::Kana,
+        Script::Han,
+        Script::Other,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Script::Latin => "latin",
+            Script::Greek => "greek",
+            Script::Cyrillic => "cyrillic",
+            Script::Hebrew => "hebrew",
+            Script::Arabic => "arabic",
+            Script::Devanagari => "devanagari",
+            Script::Thai => "thai",
+            Script::Hangul => "hangul",
+            Script::Kana => "kana",
+            Script::Han => "han",
+            Script::Other => "other",
+        }
+    }
+}
+
+impl std::str::FromStr for Script {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        Script::ALL
+            .into_iter()
+            .find(|script| script.as_str() == name)
+            .ok_or_else(|| format!("unknown script '{}'", s))
+    }
+}
+
+fn is_link(word: &str) -> bool {
+    word.contains("://") || word.to_ascii_lowercase().starts_with("www.")
+}
+
+/// Hosts of the `http(s)://` and `www.` links in `text`.
+pub fn link_hosts(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .filter_map(|word| {
+            let start = word.find("://").map(|i| i + 3).or_else(|| {
+                word.to_ascii_lowercase()
+                    .find("www.")
+                    .filter(|&i| i == 0 || !word.as_bytes()[i - 1].is_ascii_alphanumeric())
+            })?;
+            let host = word[start..]
+                .split(['/', '?', '#', ':'])
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase();
+            (!host.is_empty()).then_some(host)
+        })
+        .collect()
+}
+
+pub fn links(config: &SpamConfig, hosts: &[String]) -> Option<SpamSignal> {
+    let extra = hosts
+        .len()
+        .checked_sub(config.max_links)
+        .filter(|&n| n > 0)?;
+    Some(SpamSignal {
+        rule: SpamRule::Links,
+        points: extra as f64 * POINTS_PER_EXTRA_LINK,
+        detail: format!("{} links", hosts.len()),
+    })
+}
+
+/// Whole words or phrases from the blocklist, case and punctuation aside.
+pub fn blocked_words(config: &SpamConfig, text: &str) -> Option<SpamSignal> {
+    let padded = format!(" {} ", words(text));
+    let found = config
+        .blocked_words
+        .iter()
+        .filter(|word| {
+            let word = words(word);
+            !word.is_empty() && padded.contains(&format!(" {} ", word))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    (!found.is_empty()).then(|| SpamSignal {
+        rule: SpamRule::BlockedWord,
+        points: found.len() as f64 * POINTS_PER_BLOCKED_WORD,
+        detail: found.join(", "),
+    })
+}
+
+/// Links or email pointing to a blocklisted domain or one of its subdomains.
+pub fn blocked_domains(config: &SpamConfig, hosts: &[String], email: &str) -> Option<SpamSignal> {
+    let email_domain = email
+        .rsplit_once('@')
+        .map(|(_, domain)| domain.to_lowercase());
+    let found = hosts.iter().chain(&email_domain).find(|host| {
+        config.blocked_domains.iter().any(|blocked| {
+            let blocked = blocked.to_lowercase();
+            **host == blocked || host.ends_with(&format!(".{}", blocked))
+        })
+    })?;
+    Some(SpamSignal {
+        rule: SpamRule::BlockedDomain,
+        points: POINTS_BLOCKED_DOMAIN,
+        detail: found.clone(),
+    })
+}
+
+/// Links are left out, their case says nothing about the writer.
+pub fn all_caps(text: &str) -> Option<SpamSignal> {
+    let letters = text
+        .split_whitespace()
+        .filter(|word| !is_link(word))
+        .flat_map(str::chars)
+        .filter(|c| c.is_alphabetic());
+    let (total, upper) = letters.fold((0, 0), |(total, upper), c| {
+        (total + 1, upper + usize::from(c.is_uppercase()))
+    });
+    if total < MIN_LETTERS {
+        return None;
+    }
+    let ratio = upper as f64 / total as f64;
+    (ratio >= ALL_CAPS_RATIO).then(|| SpamSignal {
+        rule: SpamRule::AllCaps,
+        points: POINTS_ALL_CAPS,
+        detail: format!("{:.0}% uppercase", ratio * 100.0),
+    })
+}
+
+/// Messages mostly written in a script the site doesn't expect.
+pub fn script_mismatch(config: &SpamConfig, text: &str) -> Option<SpamSignal> {
+    let mut counts = HashMap::new();
+    for script in text.chars().filter_map(Script::of) {
+        *counts.entry(script).or_insert(0usize) += 1;
+    }
+    if counts.values().sum::<usize>() < MIN_LETTERS {
+        return None;
+    }
+
+    let (dominant, _) = counts.into_iter().max_by_key(|&(_, count)| count)?;
+    (!config.scripts.contains(&dominant)).then(|| SpamSignal {
+        rule: SpamRule::ScriptMismatch,
+        points: POINTS_SCRIPT_MISMATCH,
+        detail: dominant.as_str().to_string(),
+    })
+}
+
+/// Lowercase alphanumeric words separated by single spaces.
+fn words(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::contact::spam::tests::config;
+
+    #[test]
+    fn test_link_hosts() {
+        assert_eq!(
+            link_hosts("See https://Example.com/page, www.shop.io. and http://a.b:8080?x"),
+            vec!["example.com", "www.shop.io", "a.b"]
+        );
+        assert!(link_hosts("no links, just www-ish words and a@b.c").is_empty());
+    }
+
+    #[test]
+    fn test_links_beyond_the_allowance() {
+        let hosts = link_hosts("https://a.io https://b.io https://c.io https://d.io");
+
+        let signal = links(&config(), &hosts).unwrap();
+        assert_eq!(signal.points, 2.0);
+        assert_eq!(links(&config(), &hosts[..2]), None);
+    }
+
+    #[test]
+    fn test_blocked_words_match_whole_words() {
+        let signal = blocked_words(&config(), "Buy BACKLINKS now, best Casino!").unwrap();
+        assert_eq!(signal.detail, "casino, backlinks");
+        assert_eq!(signal.points, 4.0);
+
+        assert_eq!(blocked_words(&config(), "Occasionally casinos"), None);
+    }
+
+    #[test]
+    fn test_blocked_domains_include_subdomains_and_email() {
+        let hosts = link_hosts("visit https://promo.spam.example/offer");
+        assert_eq!(
+            blocked_domains(&config(), &hosts, "a@b.com")
+                .unwrap()
+                .detail,
+            "promo.spam.example"
+        );
+        assert!(blocked_domains(&config(), &[], "bot@spam.example").is_some());
+        assert!(blocked_domains(&config(), &[], "bot@notspam.example").is_none());
+    }
+
+    #[test]
+    fn test_all_caps() {
+        assert!(all_caps("AMAZING OFFER FOR YOUR BUSINESS, CALL NOW").is_some());
+        assert!(all_caps("Bonjour, je vous contacte pour un PROJET vidéo").is_none());
+        assert!(all_caps("OK THANKS").is_none());
+    }
+
+    #[test]
+    fn test_script_mismatch() {
+        let signal =
+            script_mismatch(&config(), "Здравствуйте, предлагаем продвижение сайта").unwrap();
+        assert_eq!(signal.detail, "cyrillic");
+
+        assert!(script_mismatch(&config(), "Bonjour, j'ai un projet de clip à Montréal").is_none());
+    }
+}
@@ -1,16 +1,57 @@
-use axum::{Json, extract::State, http::StatusCode};
+use axum::{
+    Json,
+    extract::State,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
 use serde_json::{Value, json};
 
-use crate::common::AppState;
+use crate::common::{AppState, auth::AdminAuth, infrastructure::storage::StorageStatus};
 
 pub async fn health_handler(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
     let uptime_secs = state.start_time.elapsed().map(|d| d.as_secs()).unwrap_or(0);
 
+    // Storage outages are reported but don't fail the check, restarting the
+    // container wouldn't bring the bucket back.
+    let storage = state.storage.status();
+    let status = if storage.backends.is_empty() || storage.backends.iter().any(|b| b.healthy) {
+        "healthy"
+    } else {
+        "degraded"
+    };
+
+    // Only the verdicts are public, backend errors may leak bucket details
+    let backends = storage
+        .backends
+        .iter()
+        .map(|b| json!({ "name": b.name, "healthy": b.healthy }))
+        .collect::<Vec<_>>();
+
     let response = json!({
-        "status": "healthy",
+        "status": status,
+        "storage": {
+            "active_backend": storage.active_backend,
+            "backends": backends
+        },
         "version": env!("CARGO_PKG_VERSION"),
         "uptime_seconds": uptime_secs,
         "timestamp": chrono::Utc::now().to_rfc3339()
     });
     (StatusCode::OK, Json(response))
 }
+
+/// Prometheus scrape endpoint, behind the admin key like other operational routes.
+pub async fn metrics_handler(_: AdminAuth, State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
+
+/// Full storage backend status, including the last error of each backend.
+pub async fn storage_status_handler(
+    _: AdminAuth,
+    State(state): State<AppState>,
+) -> Json<StorageStatus> {
+    Json(state.storage.status())
+}
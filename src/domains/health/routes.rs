@@ -1,8 +1,11 @@
 use axum::{Router, routing::get};
 
-use super::handler::health_handler;
+use super::handler::{health_handler, metrics_handler, storage_status_handler};
 use crate::common::AppState;
 
 pub fn routes() -> Router<AppState> {
-    Router::new().route("/health", get(health_handler))
+    Router::new()
+        .route("/health", get(health_handler))
+        .route("/health/storage", get(storage_status_handler))
+        .route("/metrics", get(metrics_handler))
 }
@@ -1,3 +1,5 @@
+pub mod bundle;
 pub mod contact;
 pub mod health;
+pub mod upload;
 pub mod video;
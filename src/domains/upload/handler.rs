@@ -0,0 +1,210 @@
+use axum::{Json, extract::State};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::common::{AppError, AppResult, AppState, infrastructure::storage::UploadPolicy};
+
+const DEFAULT_EXPIRATION_SECS: u64 = 600;
+
+fn default_expiration() -> u64 {
+    DEFAULT_EXPIRATION_SECS
+}
+
+struct FileName(String);
+
+impl TryFrom<String> for FileName {
+    type Error = String;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if s.is_empty() || s.len() > 255 {
+            Err("must be between 1 and 255 characters".to_string())
+        } else if s == "."
+            || s == ".."
+            || s.contains(['/', '\\'])
+            || s.chars().any(char::is_control)
+        {
+            Err("must be a plain file name".to_string())
+        } else {
+            Ok(FileName(s))
+        }
+    }
+}
+
+struct ContentType(String);
+
+impl TryFrom<String> for ContentType {
+    type Error = String;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if s.is_empty() || s.len() > 100 || !s.contains('/') {
+            Err("must be a MIME type such as image/png".to_string())
+        } else {
+            Ok(ContentType(s.to_ascii_lowercase()))
+        }
+    }
+}
+
+struct ExpiresIn(u64);
+
+impl TryFrom<u64> for ExpiresIn {
+    type Error = String;
+    fn try_from(n: u64) -> Result<Self, Self::Error> {
+        if !(60..=3600).contains(&n) {
+            Err("must be between 60 and 3600 seconds".to_string())
+        } else {
+            Ok(ExpiresIn(n))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateUploadFormInput {
+    filename: String,
+    content_type: String,
+    /// File size in bytes, signed into the URL.
+    size: u64,
+    #[serde(default = "default_expiration")]
+    expires_in: u64,
+}
+
+struct CreateUploadForm {
+    filename: FileName,
+    content_type: ContentType,
+    size: u64,
+    expires_in: ExpiresIn,
+}
+
+impl TryFrom<CreateUploadFormInput> for CreateUploadForm {
+    type Error = AppError;
+    fn try_from(input: CreateUploadFormInput) -> Result<Self, Self::Error> {
+        Ok(CreateUploadForm {
+            filename: FileName::try_from(input.filename)
+                .map_err(|e| AppError::Validation(format!("filename: {e}")))?,
+            content_type: ContentType::try_from(input.content_type)
+                .map_err(|e| AppError::Validation(format!("content_type: {e}")))?,
+            size: input.size,
+            expires_in: ExpiresIn::try_from(input.expires_in)
+                .map_err(|e| AppError::Validation(format!("expires_in: {e}")))?,
+        })
+    }
+}
+
+/// Where and how the browser uploads the file: a `PUT` of the raw file to
+/// `url`, sending `headers` unchanged.
+#[derive(Debug, Serialize)]
+pub struct UploadFormResponse {
+    pub url: String,
+    pub method: &'static str,
+    pub headers: BTreeMap<String, String>,
+    pub object_key: String,
+    pub expires_at: String,
+}
+
+/// Picks the most specific allowed content type prefix matching `content_type`.
+fn matching_prefix<'a>(allowed: &'a [String], content_type: &str) -> Option<&'a str> {
+    allowed
+        .iter()
+        .filter(|prefix| content_type.starts_with(prefix.as_str()))
+        .max_by_key(|prefix| prefix.len())
+        .map(String::as_str)
+}
+
+#[tracing::instrument(skip(state, input), fields(content_type = %input.content_type))]
+pub(super) async fn upload_form_handler(
+    State(state): State<AppState>,
+    Json(input): Json<CreateUploadFormInput>,
+) -> AppResult<Json<UploadFormResponse>> {
+    let params = CreateUploadForm::try_from(input)?;
+
+    if matching_prefix(
+        &state.config.upload_allowed_content_types,
+        &params.content_type.0,
+    )
+    .is_none()
+    {
+        return Err(AppError::Validation(
+            "content_type: not allowed".to_string(),
+        ));
+    }
+
+    let max_size = state.config.upload_max_size_bytes;
+    if !(1..=max_size).contains(&params.size) {
+        return Err(AppError::Validation(format!(
+            "size: must be between 1 and {} bytes",
+            max_size
+        )));
+    }
+
+    // Each upload gets its own folder so uploads can't overwrite each other
+    let object_key = format!(
+        "{}{}/{}",
+        state.config.upload_key_prefix,
+        Uuid::new_v4(),
+        params.filename.0
+    );
+
+    let policy = UploadPolicy {
+        content_type: params.content_type.0,
+        content_length: params.size,
+        expires_in_secs: params.expires_in.0,
+    };
+
+    let presigned = state
+        .storage
+        .generate_presigned_put(&object_key, &policy)
+        .await?;
+
+    tracing::info!(object_key = %object_key, "Upload URL generated");
+
+    Ok(Json(UploadFormResponse {
+        url: presigned.url,
+        method: "PUT",
+        headers: presigned.headers,
+        object_key,
+        expires_at: presigned.expires_at,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_input() -> CreateUploadFormInput {
+        CreateUploadFormInput {
+            filename: "brief.pdf".to_string(),
+            content_type: "application/pdf".to_string(),
+            size: 1024,
+            expires_in: 600,
+        }
+    }
+
+    #[test]
+    fn test_valid_input() {
+        assert!(CreateUploadForm::try_from(valid_input()).is_ok());
+    }
+
+    #[test]
+    fn test_filename_with_path() {
+        let input = CreateUploadFormInput {
+            filename: "../secrets.txt".to_string(),
+            ..valid_input()
+        };
+        assert!(CreateUploadForm::try_from(input).is_err());
+    }
+
+    #[test]
+    fn test_invalid_content_type() {
+        let input = CreateUploadFormInput {
+            content_type: "pdf".to_string(),
+            ..valid_input()
+        };
+        assert!(CreateUploadForm::try_from(input).is_err());
+    }
+
+    #[test]
+    fn test_matching_prefix_prefers_most_specific() {
+        let allowed = vec!["image/".to_string(), "image/png".to_string()];
+        assert_eq!(matching_prefix(&allowed, "image/png"), Some("image/png"));
+        assert_eq!(matching_prefix(&allowed, "image/jpeg"), Some("image/"));
+        assert_eq!(matching_prefix(&allowed, "text/html"), None);
+    }
+}
@@ -0,0 +1,7 @@
+use axum::{Router, routing::post};
+
+use crate::{common::AppState, domains::upload::handler::upload_form_handler};
+
+pub fn upload_routes() -> Router<AppState> {
+    Router::new().route("/upload/form", post(upload_form_handler))
+}
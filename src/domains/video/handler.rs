@@ -1,11 +1,17 @@
 use axum::{
-    Json,
+    Extension, Json,
     extract::{Query, State},
     http::StatusCode,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::common::{AppError, AppResult, AppState};
+use crate::common::{
+    AppError, AppResult, AppState,
+    audit::{AuditEntry, AuditOutcome},
+    auth::AdminAuth,
+    infrastructure::storage::{ObjectMetadata, StorageError},
+    middleware::RequestId,
+};
 
 const DEFAULT_EXPIRATION_SECS: u64 = 600;
 
@@ -100,6 +106,261 @@ pub async fn video_handler(
     ))
 }
 
+#[derive(Debug, Deserialize)]
+pub(crate) struct GetObjectMetadataInput {
+    object_key: String,
+}
+
+struct GetObjectMetadataQuery {
+    object_key: ObjectKey,
+}
+
+impl TryFrom<GetObjectMetadataInput> for GetObjectMetadataQuery {
+    type Error = AppError;
+    fn try_from(input: GetObjectMetadataInput) -> Result<Self, Self::Error> {
+        Ok(GetObjectMetadataQuery {
+            object_key: ObjectKey::try_from(input.object_key)
+                .map_err(|e| AppError::Validation(format!("object_key: {e}")))?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ObjectMetadataResponse {
+    pub object_key: String,
+    #[serde(flatten)]
+    pub metadata: ObjectMetadata,
+}
+
+#[tracing::instrument(skip(state), fields(object_key = %input.object_key))]
+pub async fn video_meta_handler(
+    _admin: AdminAuth,
+    Query(input): Query<GetObjectMetadataInput>,
+    State(state): State<AppState>,
+) -> AppResult<Json<ObjectMetadataResponse>> {
+    let params = GetObjectMetadataQuery::try_from(input)?;
+
+    let metadata = state
+        .storage
+        .head_object(params.object_key.as_ref())
+        .await?;
+
+    tracing::info!(size = metadata.size, "Object metadata retrieved");
+
+    Ok(Json(ObjectMetadataResponse {
+        object_key: params.object_key.0,
+        metadata,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DeleteObjectInput {
+    object_key: String,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+struct DeleteObjectCommand {
+    object_key: ObjectKey,
+    dry_run: bool,
+}
+
+impl TryFrom<DeleteObjectInput> for DeleteObjectCommand {
+    type Error = AppError;
+    fn try_from(input: DeleteObjectInput) -> Result<Self, Self::Error> {
+        Ok(DeleteObjectCommand {
+            object_key: ObjectKey::try_from(input.object_key)
+                .map_err(|e| AppError::Validation(format!("object_key: {e}")))?,
+            dry_run: input.dry_run,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct TransferObjectInput {
+    source_key: String,
+    destination_key: String,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+struct TransferObjectCommand {
+    source_key: ObjectKey,
+    destination_key: ObjectKey,
+    dry_run: bool,
+}
+
+impl TryFrom<TransferObjectInput> for TransferObjectCommand {
+    type Error = AppError;
+    fn try_from(input: TransferObjectInput) -> Result<Self, Self::Error> {
+        if input.source_key == input.destination_key {
+            return Err(AppError::Validation(
+                "destination_key: must differ from source_key".to_string(),
+            ));
+        }
+
+        Ok(TransferObjectCommand {
+            source_key: ObjectKey::try_from(input.source_key)
+                .map_err(|e| AppError::Validation(format!("source_key: {e}")))?,
+            destination_key: ObjectKey::try_from(input.destination_key)
+                .map_err(|e| AppError::Validation(format!("destination_key: {e}")))?,
+            dry_run: input.dry_run,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ObjectOperationResponse {
+    pub action: &'static str,
+    pub object_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_key: Option<String>,
+    pub dry_run: bool,
+}
+
+#[derive(Clone, Copy)]
+enum Transfer {
+    Copy,
+    Move,
+}
+
+impl Transfer {
+    fn action(self) -> &'static str {
+        match self {
+            Transfer::Copy => "copy",
+            Transfer::Move => "move",
+        }
+    }
+}
+
+/// Records the outcome of an admin operation in the audit trail before
+/// surfacing its error, if any.
+async fn audited(
+    state: &AppState,
+    entry: AuditEntry,
+    result: Result<(), StorageError>,
+) -> AppResult<()> {
+    let outcome = match &result {
+        Ok(()) if entry.dry_run => AuditOutcome::DryRun,
+        Ok(()) => AuditOutcome::Success,
+        Err(e) => AuditOutcome::Failure(e.to_string()),
+    };
+    state.audit.record(AuditEntry { outcome, ..entry }).await;
+    Ok(result?)
+}
+
+#[tracing::instrument(skip(state, request_id), fields(object_key = %input.object_key))]
+pub async fn delete_object_handler(
+    _admin: AdminAuth,
+    Extension(request_id): Extension<RequestId>,
+    Query(input): Query<DeleteObjectInput>,
+    State(state): State<AppState>,
+) -> AppResult<Json<ObjectOperationResponse>> {
+    let command = DeleteObjectCommand::try_from(input)?;
+    let key = command.object_key.as_ref();
+
+    let result = async {
+        state.storage.head_object(key).await?;
+        if !command.dry_run {
+            state.storage.delete_object(key).await?;
+        }
+        Ok(())
+    }
+    .await;
+
+    let entry = AuditEntry {
+        request_id: Some(request_id.0),
+        dry_run: command.dry_run,
+        ..AuditEntry::new("delete", key)
+    };
+    audited(&state, entry, result).await?;
+
+    if command.dry_run {
+        tracing::info!(dry_run = true, "Would delete object");
+    } else {
+        tracing::info!(dry_run = false, "Object deleted");
+    }
+
+    Ok(Json(ObjectOperationResponse {
+        action: "delete",
+        object_key: command.object_key.0,
+        destination_key: None,
+        dry_run: command.dry_run,
+    }))
+}
+
+pub async fn copy_object_handler(
+    admin: AdminAuth,
+    request_id: Extension<RequestId>,
+    state: State<AppState>,
+    input: Json<TransferObjectInput>,
+) -> AppResult<Json<ObjectOperationResponse>> {
+    transfer_object(admin, request_id, state, input, Transfer::Copy).await
+}
+
+pub async fn move_object_handler(
+    admin: AdminAuth,
+    request_id: Extension<RequestId>,
+    state: State<AppState>,
+    input: Json<TransferObjectInput>,
+) -> AppResult<Json<ObjectOperationResponse>> {
+    transfer_object(admin, request_id, state, input, Transfer::Move).await
+}
+
+#[tracing::instrument(
+    skip_all,
+    fields(
+        action = transfer.action(),
+        source_key = %input.source_key,
+        destination_key = %input.destination_key
+    )
+)]
+async fn transfer_object(
+    _admin: AdminAuth,
+    Extension(request_id): Extension<RequestId>,
+    State(state): State<AppState>,
+    Json(input): Json<TransferObjectInput>,
+    transfer: Transfer,
+) -> AppResult<Json<ObjectOperationResponse>> {
+    let command = TransferObjectCommand::try_from(input)?;
+    let source = command.source_key.as_ref();
+    let destination = command.destination_key.as_ref();
+
+    let result = async {
+        state.storage.head_object(source).await?;
+        if command.dry_run {
+            return Ok(());
+        }
+        match transfer {
+            Transfer::Copy => state.storage.copy_object(source, destination).await,
+            Transfer::Move => state.storage.move_object(source, destination).await,
+        }
+    }
+    .await;
+
+    let entry = AuditEntry {
+        request_id: Some(request_id.0),
+        destination: Some(destination.to_string()),
+        dry_run: command.dry_run,
+        ..AuditEntry::new(transfer.action(), source)
+    };
+    audited(&state, entry, result).await?;
+
+    match (transfer, command.dry_run) {
+        (Transfer::Copy, true) => tracing::info!(dry_run = true, "Would copy object"),
+        (Transfer::Copy, false) => tracing::info!(dry_run = false, "Object copied"),
+        (Transfer::Move, true) => tracing::info!(dry_run = true, "Would move object"),
+        (Transfer::Move, false) => tracing::info!(dry_run = false, "Object moved"),
+    }
+
+    Ok(Json(ObjectOperationResponse {
+        action: transfer.action(),
+        object_key: command.source_key.0,
+        destination_key: Some(command.destination_key.0),
+        dry_run: command.dry_run,
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,6 +413,24 @@ mod tests {
         assert!(GetPresignedVideoUrlQuery::try_from(input).is_err());
     }
 
+    #[test]
+    fn test_metadata_query_rejects_empty_key() {
+        let input = GetObjectMetadataInput {
+            object_key: "".to_string(),
+        };
+        assert!(GetObjectMetadataQuery::try_from(input).is_err());
+    }
+
+    #[test]
+    fn test_transfer_to_same_key() {
+        let input = TransferObjectInput {
+            source_key: "videos/a.mp4".to_string(),
+            destination_key: "videos/a.mp4".to_string(),
+            dry_run: false,
+        };
+        assert!(TransferObjectCommand::try_from(input).is_err());
+    }
+
     #[test]
     fn test_default_expiration() {
         assert_eq!(default_expiration(), 600);
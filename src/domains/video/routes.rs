@@ -1,7 +1,21 @@
-use axum::{Router, routing::get};
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
 
-use crate::{common::AppState, domains::video::handler::video_handler};
+use crate::{
+    common::AppState,
+    domains::video::handler::{
+        copy_object_handler, delete_object_handler, move_object_handler, video_handler,
+        video_meta_handler,
+    },
+};
 
 pub fn video_routes() -> Router<AppState> {
-    Router::new().route("/video", get(video_handler))
+    Router::new()
+        .route("/video", get(video_handler))
+        .route("/video/meta", get(video_meta_handler))
+        .route("/video/object", delete(delete_object_handler))
+        .route("/video/copy", post(copy_object_handler))
+        .route("/video/move", post(move_object_handler))
 }
@@ -69,33 +69,53 @@ async fn main() -> anyhow::Result<()> {
                     .iter()
                     .any(|suffix| s.ends_with(suffix.as_str()))
         }))
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+        .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::OPTIONS])
         .allow_headers([
             header::CONTENT_TYPE,
             header::ACCEPT,
             header::ORIGIN,
             header::RANGE,
+            header::AUTHORIZATION,
+            header::HeaderName::from_static("idempotency-key"),
+        ])
+        .expose_headers([
+            header::CONTENT_RANGE,
+            header::CONTENT_DISPOSITION,
+            header::HeaderName::from_static("idempotent-replayed"),
         ])
-        .expose_headers([header::CONTENT_RANGE])
         .allow_credentials(false);
 
-    let app_state = AppState::new(config);
+    let app_state = AppState::new(config)?;
 
     let api_routes = Router::new()
         .merge(domains::health::routes())
         .merge(domains::contact::routes())
-        .merge(domains::video::routes());
+        .merge(domains::video::routes())
+        .merge(domains::bundle::routes())
+        .merge(domains::upload::routes());
 
     let app = Router::new()
         .route("/", get(root_handler))
         .nest(&format!("/api/{}", API_VERSION), api_routes)
         .layer(
             ServiceBuilder::new()
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    utazon_backend::common::middleware::client_ip_middleware,
+                ))
                 .layer(axum::middleware::from_fn(
                     utazon_backend::common::middleware::request_id_middleware,
                 ))
                 .layer(TraceLayer::new_for_http())
                 .layer(cors)
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    utazon_backend::common::middleware::rate_limit_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    utazon_backend::common::idempotency::idempotency_middleware,
+                ))
                 .layer(DefaultBodyLimit::max(10 * 1024 * 1024))
                 .into_inner(),
         )
@@ -106,7 +126,11 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("API version: {}", API_VERSION);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
@@ -118,8 +142,11 @@ async fn root_handler() -> Json<Value> {
         "api_version": API_VERSION,
         "endpoints": {
             "health": format!("GET /api/{}/health", API_VERSION),
+            "metrics": format!("GET /api/{}/metrics - Prometheus metrics (admin)", API_VERSION),
             "contact": format!("POST /api/{}/contact - submit contact form", API_VERSION),
             "video": format!("GET /api/{}/video?object_key=<key>&expires_in=<seconds> - generate presigned URL", API_VERSION),
+            "upload": format!("POST /api/{}/upload/form - generate a presigned browser upload URL", API_VERSION),
+            "bundle": format!("GET /api/{}/bundle/<token> - download a ZIP bundle", API_VERSION),
         },
         "timestamp": chrono::Utc::now().to_rfc3339()
     }))
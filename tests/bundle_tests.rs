@@ -0,0 +1,152 @@
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use serde_json::json;
+use std::{
+    io::{Cursor, Read},
+    sync::Arc,
+};
+
+mod test_helpers;
+
+use test_helpers::{
+    MockStorage, TEST_ADMIN_API_KEY, body_bytes, body_json, create_app_with_state, send, test_state,
+};
+use utazon_backend::domains::bundle::BundleManifest;
+
+fn bundle_state() -> utazon_backend::common::AppState {
+    let manifest = BundleManifest::from_json(
+        r#"{
+            "bundles": {
+                "press-kit": {
+                    "objects": ["logos/utazon.svg"],
+                    "prefixes": ["press/"],
+                    "filename": "utazon-press-kit.zip"
+                },
+                "empty": { "prefixes": ["nothing/"] }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let storage = MockStorage::new()
+        .with_object("logos/utazon.svg", b"<svg/>")
+        .with_object("press/bio.txt", b"Utazon is a motion design studio")
+        .with_object("press/portrait.jpg", b"jpeg bytes")
+        .with_object("private/contract.pdf", b"secret");
+
+    let mut state = test_state();
+    state.storage = Arc::new(storage);
+    state.bundles = Arc::new(manifest);
+    state
+}
+
+fn token_request(body: serde_json::Value, api_key: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(Method::POST)
+        .uri("/api/v1/bundle/token")
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(key) = api_key {
+        builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", key));
+    }
+    builder.body(Body::from(body.to_string())).unwrap()
+}
+
+fn download_request(token: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(format!("/api/v1/bundle/{}", token))
+        .body(Body::empty())
+        .unwrap()
+}
+
+async fn issue_token(bundle: &str) -> String {
+    let app = create_app_with_state(bundle_state());
+    let response = send(
+        app,
+        token_request(json!({ "bundle": bundle }), Some(TEST_ADMIN_API_KEY)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    body_json(response).await["token"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_bundle_token_requires_admin() {
+    let app = create_app_with_state(bundle_state());
+    let response = send(app, token_request(json!({ "bundle": "press-kit" }), None)).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let app = create_app_with_state(bundle_state());
+    let response = send(
+        app,
+        token_request(json!({ "bundle": "press-kit" }), Some("wrong")),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_bundle_token_for_unknown_bundle() {
+    let app = create_app_with_state(bundle_state());
+    let response = send(
+        app,
+        token_request(json!({ "bundle": "private" }), Some(TEST_ADMIN_API_KEY)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_bundle_download_streams_zip() {
+    let token = issue_token("press-kit").await;
+
+    let app = create_app_with_state(bundle_state());
+    let response = send(app, download_request(&token)).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/zip");
+    assert!(
+        response.headers()[header::CONTENT_DISPOSITION]
+            .to_str()
+            .unwrap()
+            .contains("utazon-press-kit.zip")
+    );
+
+    let bytes = body_bytes(response).await;
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes.to_vec())).unwrap();
+    let mut names = archive.file_names().map(str::to_string).collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(
+        names,
+        vec!["logos/utazon.svg", "press/bio.txt", "press/portrait.jpg"]
+    );
+
+    let mut content = String::new();
+    archive
+        .by_name("press/bio.txt")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!(content, "Utazon is a motion design studio");
+}
+
+#[tokio::test]
+async fn test_bundle_download_with_invalid_token() {
+    let app = create_app_with_state(bundle_state());
+    let response = send(app, download_request("not-a-token")).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_bundle_download_without_objects() {
+    let token = issue_token("empty").await;
+
+    let app = create_app_with_state(bundle_state());
+    let response = send(app, download_request(&token)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
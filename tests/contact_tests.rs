@@ -116,3 +116,502 @@ async fn test_contact_endpoint_with_get_method() {
     // Should return method not allowed
     assert_eq!(parts.status, StatusCode::METHOD_NOT_ALLOWED);
 }
+
+#[tokio::test]
+async fn test_contact_is_stored_even_when_notifier_is_down() {
+    use axum::{body::Body, extract::ConnectInfo, http::Request};
+    use std::{net::SocketAddr, sync::Arc};
+    use test_helpers::{MockNotifier, create_app_with_state, send, test_state};
+    use utazon_backend::domains::contact::NotificationEvent;
+
+    let mut state = test_state();
+    state.notifier = Arc::new(MockNotifier::with_failure());
+    let outbox = state.outbox.clone();
+
+    let contact_form = json!({
+        "first_name": "John",
+        "last_name": "Doe",
+        "number": "+1234567890",
+        "email": "john@example.com",
+        "message": "Test",
+        "category": "quote"
+    });
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/api/v1/contact")
+        .header("content-type", "application/json")
+        .extension(ConnectInfo(SocketAddr::from(([203, 0, 113, 7], 4242))))
+        .body(Body::from(contact_form.to_string()))
+        .unwrap();
+
+    let response = send(create_app_with_state(state), request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let entries = outbox.due(i64::MAX).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    let NotificationEvent::ContactSubmitted(contact) = &entries[0].event;
+    assert_eq!(contact.email, "john@example.com");
+    assert_eq!(contact.metadata.client_ip, Some([203, 0, 113, 7].into()));
+}
+
+async fn preview(body: serde_json::Value, admin: bool) -> axum::response::Response {
+    use axum::{body::Body, http::Request};
+    use test_helpers::{TEST_ADMIN_API_KEY, create_test_app, send};
+
+    let mut request = Request::builder()
+        .method(Method::POST)
+        .uri("/api/v1/contact/templates/preview")
+        .header("content-type", "application/json");
+    if admin {
+        request = request.header("authorization", format!("Bearer {}", TEST_ADMIN_API_KEY));
+    }
+    send(
+        create_test_app(),
+        request.body(Body::from(body.to_string())).unwrap(),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_template_preview_requires_admin_key() {
+    let response = preview(json!({ "channel": "discord" }), false).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_template_preview_renders_sample_data() {
+    let response = preview(json!({ "channel": "email_subject", "locale": "en" }), true).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = test_helpers::body_json(response).await;
+    assert_eq!(body["rendered"], "New contact request · Jeanne Dupont");
+
+    let response = preview(json!({ "template": "Hello {{ first_name }}" }), true).await;
+    let body = test_helpers::body_json(response).await;
+    assert_eq!(body["rendered"], "Hello Jeanne");
+}
+
+#[tokio::test]
+async fn test_template_preview_reports_errors() {
+    let response = preview(json!({ "template": "{{ nope }}" }), true).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = preview(json!({ "channel": "discord", "locale": "xx" }), true).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+fn contact_form_with(extra: serde_json::Value) -> serde_json::Value {
+    let mut form = json!({
+        "first_name": "John",
+        "last_name": "Doe",
+        "number": "+1234567890",
+        "email": "john@example.com",
+        "message": "Test"
+    });
+    form.as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+    form
+}
+
+async fn submit(state: utazon_backend::common::AppState, form: serde_json::Value) -> StatusCode {
+    use axum::{body::Body, http::Request};
+    use test_helpers::{create_app_with_state, send};
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/api/v1/contact")
+        .header("content-type", "application/json")
+        .body(Body::from(form.to_string()))
+        .unwrap();
+    send(create_app_with_state(state), request).await.status()
+}
+
+#[tokio::test]
+async fn test_form_token_round_trip() {
+    use test_helpers::{body_json, create_app_with_state, test_config, test_state};
+
+    let mut config = test_config();
+    config.form_guard.require_token = true;
+    let mut state = test_state();
+    state.config = std::sync::Arc::new(config);
+
+    let response = test_helpers::send(
+        create_app_with_state(state.clone()),
+        axum::http::Request::get("/api/v1/contact/token")
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let token = body_json(response).await["token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let status = submit(
+        state.clone(),
+        contact_form_with(json!({ "form_token": token })),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(state.outbox.due(i64::MAX).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_bot_submissions_get_fake_success() {
+    use test_helpers::test_state;
+
+    let state = test_state();
+
+    let status = submit(
+        state.clone(),
+        contact_form_with(json!({ "website": "https://spam.example" })),
+    )
+    .await;
+    let forged = submit(
+        state.clone(),
+        contact_form_with(json!({ "form_token": "forged.token" })),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(forged, StatusCode::OK);
+    assert!(state.outbox.due(i64::MAX).await.unwrap().is_empty());
+    assert_eq!(
+        state
+            .metrics
+            .counter("contact_bot_submissions_total", &[("reason", "honeypot")]),
+        1
+    );
+    assert_eq!(
+        state.metrics.counter(
+            "contact_bot_submissions_total",
+            &[("reason", "invalid_token")]
+        ),
+        1
+    );
+}
+
+/// Brute forces a solution like the frontend does.
+fn solve(challenge: &str, difficulty: u32) -> String {
+    use sha2::{Digest, Sha256};
+
+    (0u64..)
+        .map(|counter| counter.to_string())
+        .find(|solution| {
+            let hash = Sha256::digest(format!("{}:{}", challenge, solution).as_bytes());
+            let zeros = hash.iter().take_while(|&&b| b == 0).count() as u32;
+            let bits = zeros * 8 + hash.get(zeros as usize).map_or(0, |b| b.leading_zeros());
+            bits >= difficulty
+        })
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_proof_of_work_is_verified_and_single_use() {
+    use test_helpers::{body_json, create_app_with_state, test_state};
+
+    let state = test_state();
+    let response = test_helpers::send(
+        create_app_with_state(state.clone()),
+        axum::http::Request::get("/api/v1/contact/challenge")
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    let challenge = body_json(response).await;
+    let token = challenge["challenge"].as_str().unwrap();
+    let solution = solve(token, challenge["difficulty"].as_u64().unwrap() as u32);
+    let form = contact_form_with(json!({ "pow_challenge": token, "pow_solution": solution }));
+    let mut invalid = form.clone();
+    invalid["email"] = json!("not-an-email");
+
+    // An invalid form doesn't consume the challenge
+    assert_eq!(
+        submit(state.clone(), invalid).await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(submit(state.clone(), form.clone()).await, StatusCode::OK);
+    assert_eq!(submit(state.clone(), form).await, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        state
+            .metrics
+            .counter("contact_pow_rejected_total", &[("reason", "replayed")]),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_captcha_is_checked_when_configured() {
+    use axum::{Form, Json, Router, routing::post};
+    use std::{collections::HashMap, sync::Arc};
+    use test_helpers::test_state;
+    use utazon_backend::domains::contact::captcha::TurnstileVerifier;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let stub = Router::new().route(
+        "/siteverify",
+        post(|Form(form): Form<HashMap<String, String>>| async move {
+            Json(json!({ "success": form.get("response").map(String::as_str) == Some("valid") }))
+        }),
+    );
+    tokio::spawn(async move { axum::serve(listener, stub).await.unwrap() });
+
+    let mut state = test_state();
+    state.captcha = Some(Arc::new(TurnstileVerifier::new(
+        reqwest::Client::new(),
+        "secret".to_string(),
+        format!("http://{}/siteverify", addr),
+    )));
+
+    let missing = submit(state.clone(), contact_form_with(json!({}))).await;
+    let rejected = submit(
+        state.clone(),
+        contact_form_with(json!({ "captcha_token": "forged" })),
+    )
+    .await;
+    // The captcha is checked before the form, whatever is wrong with it
+    let invalid_form = submit(
+        state.clone(),
+        contact_form_with(json!({ "email": "invalid", "captcha_token": "forged" })),
+    )
+    .await;
+    let accepted = submit(
+        state.clone(),
+        contact_form_with(json!({ "cf-turnstile-response": "valid" })),
+    )
+    .await;
+
+    assert_eq!(missing, StatusCode::BAD_REQUEST);
+    assert_eq!(rejected, StatusCode::BAD_REQUEST);
+    assert_eq!(invalid_form, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        state.metrics.counter(
+            "contact_captcha_rejected_total",
+            &[("provider", "turnstile")]
+        ),
+        2
+    );
+    assert_eq!(accepted, StatusCode::OK);
+    assert_eq!(state.outbox.due(i64::MAX).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_client_ip_is_taken_from_trusted_proxy() {
+    use axum::{body::Body, extract::ConnectInfo, http::Request};
+    use std::{net::SocketAddr, sync::Arc};
+    use test_helpers::{create_app_with_state, send, test_config, test_state};
+    use utazon_backend::common::{PublicConfig, middleware::TrustedProxies};
+    use utazon_backend::domains::contact::NotificationEvent;
+
+    let mut state = test_state();
+    state.config = Arc::new(PublicConfig {
+        trusted_proxies: TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap()]),
+        ..test_config()
+    });
+    let outbox = state.outbox.clone();
+    let app = create_app_with_state(state);
+    let from = |peer: [u8; 4]| {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/api/v1/contact")
+            .header("content-type", "application/json")
+            .header("x-forwarded-for", "203.0.113.7")
+            .extension(ConnectInfo(SocketAddr::from((peer, 4242))))
+            .body(Body::from(contact_form_with(json!({})).to_string()))
+            .unwrap()
+    };
+
+    send(app.clone(), from([10, 0, 0, 2])).await;
+    // Not a proxy, the header is spoofed
+    send(app, from([198, 51, 100, 1])).await;
+
+    let client_ips = outbox
+        .due(i64::MAX)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|entry| {
+            let NotificationEvent::ContactSubmitted(contact) = entry.event;
+            contact.metadata.client_ip.unwrap().to_string()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(client_ips, vec!["203.0.113.7", "198.51.100.1"]);
+}
+
+#[tokio::test]
+async fn test_idempotent_retry_is_not_submitted_twice() {
+    use axum::{body::Body, http::Request};
+    use test_helpers::{body_json, create_app_with_state, send, test_state};
+
+    let state = test_state();
+    let outbox = state.outbox.clone();
+    let app = create_app_with_state(state);
+    let submit = |message: &str| {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/api/v1/contact")
+            .header("content-type", "application/json")
+            .header("idempotency-key", "3f1c9a52-retry")
+            .body(Body::from(
+                contact_form_with(json!({ "message": message })).to_string(),
+            ))
+            .unwrap()
+    };
+
+    let first = send(app.clone(), submit("Hello")).await;
+    assert_eq!(first.status(), StatusCode::OK);
+    assert!(first.headers().get("idempotent-replayed").is_none());
+
+    let retry = send(app.clone(), submit("Hello")).await;
+    assert_eq!(retry.status(), StatusCode::OK);
+    assert_eq!(retry.headers()["idempotent-replayed"], "true");
+    assert_eq!(body_json(retry).await["success"], true);
+    assert_eq!(outbox.due(i64::MAX).await.unwrap().len(), 1);
+
+    let reused = send(app, submit("Something else")).await;
+    assert_eq!(reused.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_resubmission_is_flagged_or_suppressed() {
+    use std::sync::Arc;
+    use test_helpers::test_state;
+    use utazon_backend::domains::contact::{
+        DuplicateAction, DuplicateConfig, DuplicateDetector, NotificationEvent,
+    };
+
+    for action in [DuplicateAction::Flag, DuplicateAction::Suppress] {
+        let mut state = test_state();
+        state.duplicates = Arc::new(DuplicateDetector::new(DuplicateConfig {
+            window: std::time::Duration::from_secs(3600),
+            similarity_threshold: 0.8,
+            action,
+        }));
+        let outbox = state.outbox.clone();
+        let message = "We'd like a teaser for our product launch next month, are you available?";
+
+        let first = contact_form_with(json!({ "message": message }));
+        let again = contact_form_with(json!({ "message": format!("{message}!!") }));
+        assert_eq!(submit(state.clone(), first).await, StatusCode::OK);
+        assert_eq!(submit(state, again).await, StatusCode::OK);
+
+        let entries = outbox.due(i64::MAX).await.unwrap();
+        let duplicates = entries
+            .iter()
+            .map(|entry| {
+                let NotificationEvent::ContactSubmitted(contact) = &entry.event;
+                contact.duplicate_of.is_some()
+            })
+            .collect::<Vec<_>>();
+        match action {
+            DuplicateAction::Flag => assert_eq!(duplicates, vec![false, true]),
+            DuplicateAction::Suppress => assert_eq!(duplicates, vec![false]),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_concurrent_resubmissions_are_flagged() {
+    use test_helpers::test_state;
+    use utazon_backend::domains::contact::NotificationEvent;
+
+    let state = test_state();
+    let outbox = state.outbox.clone();
+    let form = contact_form_with(json!({
+        "message": "We'd like a teaser for our product launch next month, are you available?"
+    }));
+
+    let statuses =
+        futures_util::future::join_all((0..4).map(|_| submit(state.clone(), form.clone()))).await;
+
+    assert!(statuses.iter().all(|status| *status == StatusCode::OK));
+    let originals = outbox
+        .due(i64::MAX)
+        .await
+        .unwrap()
+        .iter()
+        .filter(|entry| {
+            let NotificationEvent::ContactSubmitted(contact) = &entry.event;
+            contact.duplicate_of.is_none()
+        })
+        .count();
+    assert_eq!(originals, 1);
+}
+
+async fn admin(
+    app: axum::Router,
+    method: Method,
+    uri: &str,
+    body: Option<serde_json::Value>,
+) -> axum::response::Response {
+    use axum::{body::Body, http::Request};
+    use test_helpers::{TEST_ADMIN_API_KEY, send};
+
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", TEST_ADMIN_API_KEY))
+        .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+        .unwrap();
+    send(app, request).await
+}
+
+#[tokio::test]
+async fn test_spam_is_quarantined_until_released() {
+    use test_helpers::{body_json, create_app_with_state, test_state};
+
+    let state = test_state();
+    let outbox = state.outbox.clone();
+    let app = create_app_with_state(state.clone());
+    let spam = contact_form_with(json!({
+        "message": "Best CASINO bonus, buy backlinks at https://promo.spam.example"
+    }));
+
+    assert_eq!(submit(state.clone(), spam).await, StatusCode::OK);
+    assert!(outbox.due(i64::MAX).await.unwrap().is_empty());
+
+    let quarantined =
+        body_json(admin(app.clone(), Method::GET, "/api/v1/contact/quarantine", None).await).await;
+    assert_eq!(quarantined.as_array().unwrap().len(), 1);
+    assert!(quarantined[0]["verdict"]["score"].as_f64().unwrap() >= 5.0);
+    assert_eq!(quarantined[0]["event"]["email"], "john@example.com");
+    let id = quarantined[0]["id"].as_i64().unwrap();
+
+    let release = format!("/api/v1/contact/quarantine/{id}/release");
+    let released = admin(app.clone(), Method::POST, &release, None).await;
+    assert_eq!(released.status(), StatusCode::OK);
+    assert_eq!(outbox.due(i64::MAX).await.unwrap().len(), 1);
+
+    let again = admin(app, Method::POST, &release, None).await;
+    assert_eq!(again.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_spam_training_requires_admin_key() {
+    use test_helpers::{body_json, create_app_with_state, test_state};
+
+    let app = create_app_with_state(test_state());
+    let response = request_with_json(
+        Method::POST,
+        "/api/v1/contact/spam/train",
+        json!({ "message": "cheap backlinks", "spam": true }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let trained = admin(
+        app,
+        Method::POST,
+        "/api/v1/contact/spam/train",
+        Some(json!({ "message": "cheap backlinks", "spam": true })),
+    )
+    .await;
+    assert_eq!(
+        body_json(trained).await,
+        json!({ "spam_messages": 1, "ham_messages": 0 })
+    );
+}
@@ -1,8 +1,16 @@
-use axum::http::{Method, StatusCode};
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use std::sync::Arc;
 
 mod test_helpers;
 
-use test_helpers::request;
+use test_helpers::{
+    MockStorage, TEST_ADMIN_API_KEY, body_bytes, body_json, create_app_with_state, request, send,
+    test_state,
+};
+use utazon_backend::common::infrastructure::storage::FailoverStorage;
 
 #[tokio::test]
 async fn test_health_endpoint() {
@@ -20,6 +28,7 @@ async fn test_health_endpoint() {
     assert!(json.get("version").is_some());
     assert!(json.get("uptime_seconds").is_some());
     assert!(json.get("timestamp").is_some());
+    assert_eq!(json["storage"]["active_backend"], "primary");
 }
 
 #[tokio::test]
@@ -36,3 +45,128 @@ async fn test_health_endpoint_returns_json() {
 
     assert!(content_type.contains("application/json"));
 }
+
+#[tokio::test]
+async fn test_health_endpoint_reports_failover_backend() {
+    let storage = FailoverStorage::new(
+        Arc::new(MockStorage::with_failure()),
+        Arc::new(MockStorage::new()),
+    );
+    storage.probe().await;
+    storage.probe().await;
+
+    let mut state = test_state();
+    state.storage = Arc::new(storage);
+
+    let response = send(
+        create_app_with_state(state),
+        Request::builder()
+            .uri("/api/v1/health")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    assert_eq!(json["status"], "healthy");
+    assert_eq!(json["storage"]["active_backend"], "secondary");
+    assert_eq!(json["storage"]["backends"][0]["healthy"], false);
+    assert_eq!(json["storage"]["backends"][1]["healthy"], true);
+    assert!(json["storage"]["backends"][0].get("last_error").is_none());
+}
+
+#[tokio::test]
+async fn test_storage_status_requires_admin_key() {
+    let storage = FailoverStorage::new(
+        Arc::new(MockStorage::with_failure()),
+        Arc::new(MockStorage::new()),
+    );
+    storage.probe().await;
+
+    let mut state = test_state();
+    state.storage = Arc::new(storage);
+    let app = create_app_with_state(state);
+    let status_request = |key: Option<&str>| {
+        let mut builder = Request::builder().uri("/api/v1/health/storage");
+        if let Some(key) = key {
+            builder = builder.header("authorization", format!("Bearer {}", key));
+        }
+        builder.body(Body::empty()).unwrap()
+    };
+
+    let anonymous = send(app.clone(), status_request(None)).await;
+    assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+
+    let admin = send(app, status_request(Some(TEST_ADMIN_API_KEY))).await;
+    assert_eq!(admin.status(), StatusCode::OK);
+    let json = body_json(admin).await;
+    assert_eq!(json["backends"][0]["consecutive_failures"], 1);
+    assert!(json["backends"][0]["last_error"].is_string());
+}
+
+#[tokio::test]
+async fn test_metrics_requires_admin_key() {
+    let response = request(Method::GET, "/api/v1/metrics").await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_metrics_renders_prometheus_text() {
+    let state = test_state();
+    state
+        .metrics
+        .incr("discord_rate_limited_total", &[("scope", "user")]);
+
+    let response = send(
+        create_app_with_state(state),
+        Request::builder()
+            .method(Method::GET)
+            .uri("/api/v1/metrics")
+            .header("authorization", format!("Bearer {}", TEST_ADMIN_API_KEY))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = String::from_utf8(body_bytes(response).await.to_vec()).unwrap();
+    assert!(body.contains("discord_rate_limited_total{scope=\"user\"} 1"));
+}
+
+#[tokio::test]
+async fn test_health_is_rate_limited_per_client_ip() {
+    use axum::extract::ConnectInfo;
+    use std::net::SocketAddr;
+    use utazon_backend::common::middleware::RateLimiter;
+
+    let mut state = test_state();
+    state.rate_limiter = Arc::new(RateLimiter::new(vec![
+        "/api/v1/health=2/60".parse().unwrap(),
+    ]));
+    let app = create_app_with_state(state);
+    let from = |ip: [u8; 4]| {
+        Request::builder()
+            .uri("/api/v1/health")
+            .extension(ConnectInfo(SocketAddr::from((ip, 4242))))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let first = send(app.clone(), from([203, 0, 113, 7])).await;
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(first.headers()["ratelimit-limit"], "2");
+    assert_eq!(first.headers()["ratelimit-remaining"], "1");
+    assert_eq!(first.headers()["ratelimit-policy"], "2;w=60");
+
+    send(app.clone(), from([203, 0, 113, 7])).await;
+    let limited = send(app.clone(), from([203, 0, 113, 7])).await;
+    assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(limited.headers()["retry-after"], "30");
+    assert_eq!(limited.headers()["ratelimit-remaining"], "0");
+    assert_eq!(body_json(limited).await["error"], "Too many requests");
+
+    let other = send(app, from([198, 51, 100, 1])).await;
+    assert_eq!(other.status(), StatusCode::OK);
+}
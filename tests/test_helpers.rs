@@ -4,26 +4,77 @@ use axum::{
     body::Body,
     http::{Method, Request, header},
 };
-use std::sync::Arc;
+use bytes::Bytes;
+use futures_util::stream;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
 use tower::ServiceExt;
 
-use utazon_backend::common::infrastructure::storage::{StorageClient, StorageError};
+use utazon_backend::common::audit::{AuditEntry, AuditLog};
+use utazon_backend::common::idempotency::IdempotencyStore;
+use utazon_backend::common::infrastructure::storage::{
+    ObjectMetadata, ObjectStream, PresignedPut, StorageClient, StorageError, UploadPolicy,
+};
+use utazon_backend::common::metrics::Metrics;
+use utazon_backend::common::middleware::{RateLimiter, TrustedProxies};
+use utazon_backend::common::signing::Signer;
 use utazon_backend::common::{AppError, AppResult, AppState, PublicConfig, Secrets};
-use utazon_backend::domains::contact::service::Notification;
+use utazon_backend::domains::bundle::BundleManifest;
+use utazon_backend::domains::contact::service::{
+    Notification, NotificationEvent, NotificationTemplates,
+};
+use utazon_backend::domains::contact::{
+    DuplicateAction, DuplicateConfig, DuplicateDetector, EmailPolicy, FormGuardConfig, Outbox,
+    OutboxConfig, PowConfig, ProofOfWork, SpamConfig, SpamFilter, spam::Script,
+};
+
+pub const TEST_ADMIN_API_KEY: &str = "test_admin_key";
+pub const TEST_SIGNING_SECRET: &str = "test_signing_secret";
 
 #[derive(Clone)]
 pub struct MockStorage {
     pub should_fail: bool,
+    pub objects: Arc<Mutex<BTreeMap<String, Bytes>>>,
+    pub metadata: HashMap<String, ObjectMetadata>,
 }
 
 impl MockStorage {
     pub fn new() -> Self {
-        Self { should_fail: false }
+        Self {
+            should_fail: false,
+            objects: Arc::new(Mutex::new(BTreeMap::new())),
+            metadata: HashMap::new(),
+        }
     }
 
     #[allow(dead_code)]
     pub fn with_failure() -> Self {
-        Self { should_fail: true }
+        Self {
+            should_fail: true,
+            ..Self::new()
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn with_object(self, key: &str, content: &'static [u8]) -> Self {
+        self.objects
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), Bytes::from_static(content));
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn object_keys(&self) -> Vec<String> {
+        self.objects.lock().unwrap().keys().cloned().collect()
+    }
+
+    #[allow(dead_code)]
+    pub fn with_metadata(mut self, key: &str, metadata: ObjectMetadata) -> Self {
+        self.metadata.insert(key.to_string(), metadata);
+        self
     }
 }
 
@@ -49,6 +100,113 @@ impl StorageClient for MockStorage {
             object_key, expires_in_secs
         ))
     }
+
+    async fn generate_presigned_put(
+        &self,
+        object_key: &str,
+        policy: &UploadPolicy,
+    ) -> Result<PresignedPut, StorageError> {
+        if self.should_fail {
+            return Err(StorageError::S3Error("Mock storage error".to_string()));
+        }
+
+        Ok(PresignedPut {
+            url: format!(
+                "https://mock-r2.com/bucket/{}?expires={}",
+                object_key, policy.expires_in_secs
+            ),
+            headers: [
+                ("content-type".to_string(), policy.content_type.clone()),
+                (
+                    "content-length".to_string(),
+                    policy.content_length.to_string(),
+                ),
+            ]
+            .into(),
+            expires_at: "2030-01-01T00:00:00+00:00".to_string(),
+        })
+    }
+
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        if self.should_fail {
+            return Err(StorageError::S3Error("Mock storage error".to_string()));
+        }
+
+        Ok(self
+            .objects
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_object(&self, object_key: &str) -> Result<ObjectStream, StorageError> {
+        let content = self
+            .objects
+            .lock()
+            .unwrap()
+            .get(object_key)
+            .cloned()
+            .ok_or_else(|| StorageError::NotFound(object_key.to_string()))?;
+
+        Ok(Box::pin(stream::iter([Ok(content)])))
+    }
+
+    async fn head_object(&self, object_key: &str) -> Result<ObjectMetadata, StorageError> {
+        if self.should_fail {
+            return Err(StorageError::S3Error("Mock storage error".to_string()));
+        }
+
+        if let Some(metadata) = self.metadata.get(object_key) {
+            return Ok(metadata.clone());
+        }
+
+        self.objects
+            .lock()
+            .unwrap()
+            .get(object_key)
+            .map(|content| ObjectMetadata {
+                size: content.len() as u64,
+                ..ObjectMetadata::default()
+            })
+            .ok_or_else(|| StorageError::NotFound(object_key.to_string()))
+    }
+
+    async fn delete_object(&self, object_key: &str) -> Result<(), StorageError> {
+        if self.should_fail {
+            return Err(StorageError::S3Error("Mock storage error".to_string()));
+        }
+
+        self.objects.lock().unwrap().remove(object_key);
+        Ok(())
+    }
+
+    async fn copy_object(
+        &self,
+        source_key: &str,
+        destination_key: &str,
+    ) -> Result<(), StorageError> {
+        if self.should_fail {
+            return Err(StorageError::S3Error("Mock storage error".to_string()));
+        }
+
+        let mut objects = self.objects.lock().unwrap();
+        let content = objects
+            .get(source_key)
+            .cloned()
+            .ok_or_else(|| StorageError::NotFound(source_key.to_string()))?;
+        objects.insert(destination_key.to_string(), content);
+        Ok(())
+    }
+
+    async fn check_health(&self) -> Result<(), StorageError> {
+        if self.should_fail {
+            return Err(StorageError::S3Error("Mock storage error".to_string()));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone)]
@@ -75,7 +233,11 @@ impl Default for MockNotifier {
 
 #[async_trait]
 impl Notification for MockNotifier {
-    async fn notify(&self, _message: String) -> AppResult<()> {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    async fn notify(&self, _event: &NotificationEvent) -> AppResult<()> {
         if self.should_fail {
             return Err(AppError::DiscordApi("Mock notifier error".to_string()));
         }
@@ -83,7 +245,39 @@ impl Notification for MockNotifier {
     }
 }
 
+#[derive(Clone, Default)]
+pub struct MockAuditLog {
+    pub entries: Arc<Mutex<Vec<AuditEntry>>>,
+}
+
+#[async_trait]
+impl AuditLog for MockAuditLog {
+    async fn record(&self, entry: AuditEntry) {
+        self.entries.lock().unwrap().push(entry);
+    }
+}
+
 pub fn create_test_app() -> Router {
+    create_app_with_state(test_state())
+}
+
+pub fn test_config() -> PublicConfig {
+    PublicConfig {
+        discord_user_ids: vec!["test_user_id".to_string()],
+        r2_account_id: "test_account_id".to_string(),
+        upload_key_prefix: "uploads/briefs/".to_string(),
+        upload_allowed_content_types: vec!["image/".to_string(), "application/pdf".to_string()],
+        upload_max_size_bytes: 10 * 1024 * 1024,
+        form_guard: FormGuardConfig {
+            min_fill_time: std::time::Duration::ZERO,
+            token_ttl: std::time::Duration::from_secs(3600),
+            require_token: false,
+        },
+        trusted_proxies: TrustedProxies::default(),
+    }
+}
+
+pub fn test_state() -> AppState {
     let http_client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(10))
         .build()
@@ -92,26 +286,64 @@ pub fn create_test_app() -> Router {
     let storage = Arc::new(MockStorage::new());
     let notifier = Arc::new(MockNotifier::new());
 
-    let app_state = AppState {
-        config: Arc::new(PublicConfig {
-            discord_user_ids: vec!["test_user_id".to_string()],
-            r2_account_id: "test_account_id".to_string(),
-        }),
+    AppState {
+        config: Arc::new(test_config()),
         secrets: Arc::new(Secrets {
-            discord_bot_token: "test_token".to_string(),
+            discord_bot_token: Some("test_token".to_string()),
+            smtp_password: None,
             r2_access_key_id: "test_access_key_id".to_string(),
             r2_secret_access_key: "test_secret_access_key".to_string(),
+            admin_api_key: Some(TEST_ADMIN_API_KEY.to_string()),
         }),
         http_client,
         start_time: std::time::SystemTime::now(),
         storage,
         notifier,
-    };
-
-    create_app_with_state(app_state)
+        templates: Arc::new(NotificationTemplates::builtin()),
+        pow: Arc::new(ProofOfWork::new(PowConfig {
+            required: false,
+            base_difficulty: 4,
+            max_difficulty: 8,
+            ttl: std::time::Duration::from_secs(600),
+            abuse_window: std::time::Duration::from_secs(600),
+            abuse_threshold: 5,
+        })),
+        email_policy: Arc::new(EmailPolicy::new(None).unwrap()),
+        duplicates: Arc::new(DuplicateDetector::new(DuplicateConfig {
+            window: std::time::Duration::from_secs(3600),
+            similarity_threshold: 0.8,
+            action: DuplicateAction::Flag,
+        })),
+        spam: Arc::new(
+            SpamFilter::new(SpamConfig {
+                quarantine_threshold: 5.0,
+                max_links: 2,
+                blocked_words: vec!["casino".to_string(), "backlinks".to_string()],
+                blocked_domains: vec!["spam.example".to_string()],
+                scripts: vec![Script::Latin],
+                model_path: None,
+            })
+            .unwrap(),
+        ),
+        captcha: None,
+        outbox: Arc::new(
+            Outbox::in_memory(OutboxConfig {
+                max_attempts: 3,
+                retry_base_delay: std::time::Duration::ZERO,
+                poll_interval: std::time::Duration::from_secs(1),
+            })
+            .expect("Failed to open in-memory outbox"),
+        ),
+        signer: Arc::new(Signer::new(TEST_SIGNING_SECRET)),
+        bundles: Arc::new(BundleManifest::default()),
+        audit: Arc::new(MockAuditLog::default()),
+        metrics: Arc::new(Metrics::new()),
+        rate_limiter: Arc::new(RateLimiter::new(vec![])),
+        idempotency: Arc::new(IdempotencyStore::new(std::time::Duration::from_secs(3600))),
+    }
 }
 
-fn create_app_with_state(app_state: AppState) -> Router {
+pub fn create_app_with_state(app_state: AppState) -> Router {
     use axum::{
         Json,
         extract::DefaultBodyLimit,
@@ -141,7 +373,9 @@ fn create_app_with_state(app_state: AppState) -> Router {
     let api_routes = Router::new()
         .merge(utazon_backend::domains::health::routes())
         .merge(utazon_backend::domains::contact::routes())
-        .merge(utazon_backend::domains::video::routes());
+        .merge(utazon_backend::domains::video::routes())
+        .merge(utazon_backend::domains::bundle::routes())
+        .merge(utazon_backend::domains::upload::routes());
 
     let cors = CorsLayer::new()
         .allow_origin("http://localhost:3000".parse::<HeaderValue>().unwrap())
@@ -159,17 +393,30 @@ fn create_app_with_state(app_state: AppState) -> Router {
         .nest(&format!("/api/{}", API_VERSION), api_routes)
         .layer(
             ServiceBuilder::new()
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    utazon_backend::common::middleware::client_ip_middleware,
+                ))
                 .layer(axum::middleware::from_fn(
                     utazon_backend::common::middleware::request_id_middleware,
                 ))
                 .layer(TraceLayer::new_for_http())
                 .layer(cors)
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    utazon_backend::common::middleware::rate_limit_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    utazon_backend::common::idempotency::idempotency_middleware,
+                ))
                 .layer(DefaultBodyLimit::max(10 * 1024 * 1024))
                 .into_inner(),
         )
         .with_state(app_state)
 }
 
+#[allow(dead_code)]
 pub async fn request(method: Method, uri: &str) -> axum::response::Response {
     let app = create_test_app();
 
@@ -182,6 +429,23 @@ pub async fn request(method: Method, uri: &str) -> axum::response::Response {
     app.oneshot(request).await.unwrap()
 }
 
+#[allow(dead_code)]
+pub async fn send(app: Router, request: Request<Body>) -> axum::response::Response {
+    app.oneshot(request).await.unwrap()
+}
+
+#[allow(dead_code)]
+pub async fn body_bytes(response: axum::response::Response) -> Bytes {
+    axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap()
+}
+
+#[allow(dead_code)]
+pub async fn body_json(response: axum::response::Response) -> serde_json::Value {
+    serde_json::from_slice(&body_bytes(response).await).unwrap()
+}
+
 #[allow(dead_code)]
 pub async fn request_with_json(
     method: Method,
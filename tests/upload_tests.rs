@@ -0,0 +1,69 @@
+use axum::http::{Method, StatusCode};
+use serde_json::json;
+
+mod test_helpers;
+
+use test_helpers::{body_json, request_with_json};
+
+#[tokio::test]
+async fn test_upload_form_returns_presigned_put() {
+    let response = request_with_json(
+        Method::POST,
+        "/api/v1/upload/form",
+        json!({ "filename": "brief.pdf", "content_type": "application/pdf", "size": 2048 }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+
+    assert_eq!(json["method"], "PUT");
+    assert_eq!(json["headers"]["content-type"], "application/pdf");
+    assert_eq!(json["headers"]["content-length"], "2048");
+
+    let key = json["object_key"].as_str().unwrap();
+    assert!(key.starts_with("uploads/briefs/"));
+    assert!(key.ends_with("/brief.pdf"));
+    assert_eq!(
+        json["url"],
+        format!("https://mock-r2.com/bucket/{}?expires=600", key)
+    );
+}
+
+#[tokio::test]
+async fn test_upload_form_rejects_disallowed_content_type() {
+    let response = request_with_json(
+        Method::POST,
+        "/api/v1/upload/form",
+        json!({ "filename": "page.html", "content_type": "text/html", "size": 10 }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_upload_form_rejects_path_in_filename() {
+    let response = request_with_json(
+        Method::POST,
+        "/api/v1/upload/form",
+        json!({ "filename": "../../index.html", "content_type": "image/png", "size": 10 }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_upload_form_rejects_oversized_file() {
+    for size in [0, 10 * 1024 * 1024 + 1] {
+        let response = request_with_json(
+            Method::POST,
+            "/api/v1/upload/form",
+            json!({ "filename": "brief.pdf", "content_type": "application/pdf", "size": size }),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "size {}", size);
+    }
+}
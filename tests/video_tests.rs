@@ -0,0 +1,313 @@
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use serde_json::json;
+use std::{collections::HashMap, sync::Arc};
+
+mod test_helpers;
+
+use test_helpers::{
+    MockAuditLog, MockStorage, TEST_ADMIN_API_KEY, body_json, create_app_with_state, request, send,
+    test_state,
+};
+use utazon_backend::common::{audit::AuditOutcome, infrastructure::storage::ObjectMetadata};
+
+fn meta_request(object_key: &str, api_key: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/api/v1/video/meta?object_key={}", object_key));
+    if let Some(key) = api_key {
+        builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", key));
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+fn app_with_metadata() -> axum::Router {
+    let storage = MockStorage::new().with_metadata(
+        "videos/reel.mp4",
+        ObjectMetadata {
+            size: 1_048_576,
+            content_type: Some("video/mp4".to_string()),
+            etag: Some("\"abc123\"".to_string()),
+            last_modified: Some("2025-01-01T00:00:00Z".to_string()),
+            metadata: HashMap::from([("client".to_string(), "utazon".to_string())]),
+        },
+    );
+
+    let mut state = test_state();
+    state.storage = Arc::new(storage);
+    create_app_with_state(state)
+}
+
+#[tokio::test]
+async fn test_video_endpoint_returns_presigned_url() {
+    let response = request(Method::GET, "/api/v1/video?object_key=videos/reel.mp4").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    assert_eq!(json["expires_in"], 600);
+    assert!(json["url"].as_str().unwrap().contains("videos/reel.mp4"));
+}
+
+#[tokio::test]
+async fn test_video_meta_returns_metadata() {
+    let response = send(
+        app_with_metadata(),
+        meta_request("videos/reel.mp4", Some(TEST_ADMIN_API_KEY)),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    assert_eq!(json["object_key"], "videos/reel.mp4");
+    assert_eq!(json["size"], 1_048_576);
+    assert_eq!(json["content_type"], "video/mp4");
+    assert_eq!(json["etag"], "\"abc123\"");
+    assert_eq!(json["last_modified"], "2025-01-01T00:00:00Z");
+    assert_eq!(json["metadata"]["client"], "utazon");
+}
+
+#[tokio::test]
+async fn test_video_meta_requires_admin() {
+    let response = send(app_with_metadata(), meta_request("videos/reel.mp4", None)).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_video_meta_unknown_object() {
+    let response = send(
+        app_with_metadata(),
+        meta_request("videos/missing.mp4", Some(TEST_ADMIN_API_KEY)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+fn admin_app(storage: MockStorage, audit: MockAuditLog) -> axum::Router {
+    let mut state = test_state();
+    state.storage = Arc::new(storage);
+    state.audit = Arc::new(audit);
+    create_app_with_state(state)
+}
+
+fn admin_request(method: Method, uri: &str, body: Option<serde_json::Value>) -> Request<Body> {
+    let builder = Request::builder().method(method).uri(uri).header(
+        header::AUTHORIZATION,
+        format!("Bearer {}", TEST_ADMIN_API_KEY),
+    );
+    match body {
+        Some(json) => builder
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(json.to_string()))
+            .unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+fn renders() -> MockStorage {
+    MockStorage::new()
+        .with_object("renders/v1.mp4", b"v1")
+        .with_object("renders/v2.mp4", b"v2")
+}
+
+#[tokio::test]
+async fn test_delete_object() {
+    let storage = renders();
+    let audit = MockAuditLog::default();
+
+    let response = send(
+        admin_app(storage.clone(), audit.clone()),
+        admin_request(
+            Method::DELETE,
+            "/api/v1/video/object?object_key=renders/v1.mp4",
+            None,
+        ),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(storage.object_keys(), vec!["renders/v2.mp4"]);
+
+    let entries = audit.entries.lock().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].action, "delete");
+    assert_eq!(entries[0].target, "renders/v1.mp4");
+    assert_eq!(entries[0].outcome, AuditOutcome::Success);
+    assert!(entries[0].request_id.is_some());
+}
+
+#[tokio::test]
+async fn test_delete_object_dry_run() {
+    let storage = renders();
+    let audit = MockAuditLog::default();
+
+    let response = send(
+        admin_app(storage.clone(), audit.clone()),
+        admin_request(
+            Method::DELETE,
+            "/api/v1/video/object?object_key=renders/v1.mp4&dry_run=true",
+            None,
+        ),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await["dry_run"], true);
+    assert_eq!(storage.object_keys().len(), 2);
+    let entries = audit.entries.lock().unwrap();
+    assert!(entries[0].dry_run);
+    assert_eq!(entries[0].outcome, AuditOutcome::DryRun);
+}
+
+#[tokio::test]
+async fn test_delete_missing_object_is_audited() {
+    let audit = MockAuditLog::default();
+
+    let response = send(
+        admin_app(renders(), audit.clone()),
+        admin_request(
+            Method::DELETE,
+            "/api/v1/video/object?object_key=renders/v3.mp4",
+            None,
+        ),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(matches!(
+        audit.entries.lock().unwrap()[0].outcome,
+        AuditOutcome::Failure(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_copy_object() {
+    let storage = renders();
+
+    let response = send(
+        admin_app(storage.clone(), MockAuditLog::default()),
+        admin_request(
+            Method::POST,
+            "/api/v1/video/copy",
+            Some(json!({
+                "source_key": "renders/v1.mp4",
+                "destination_key": "archive/v1.mp4"
+            })),
+        ),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        storage.object_keys(),
+        vec!["archive/v1.mp4", "renders/v1.mp4", "renders/v2.mp4"]
+    );
+}
+
+#[tokio::test]
+async fn test_move_object() {
+    let storage = renders();
+    let audit = MockAuditLog::default();
+
+    let response = send(
+        admin_app(storage.clone(), audit.clone()),
+        admin_request(
+            Method::POST,
+            "/api/v1/video/move",
+            Some(json!({
+                "source_key": "renders/v1.mp4",
+                "destination_key": "archive/v1.mp4"
+            })),
+        ),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    assert_eq!(json["action"], "move");
+    assert_eq!(json["destination_key"], "archive/v1.mp4");
+    assert_eq!(
+        storage.object_keys(),
+        vec!["archive/v1.mp4", "renders/v2.mp4"]
+    );
+    assert_eq!(
+        audit.entries.lock().unwrap()[0].destination.as_deref(),
+        Some("archive/v1.mp4")
+    );
+}
+
+#[tokio::test]
+async fn test_move_object_dry_run() {
+    let storage = renders();
+    let audit = MockAuditLog::default();
+
+    let response = send(
+        admin_app(storage.clone(), audit.clone()),
+        admin_request(
+            Method::POST,
+            "/api/v1/video/move",
+            Some(json!({
+                "source_key": "renders/v1.mp4",
+                "destination_key": "archive/v1.mp4",
+                "dry_run": true
+            })),
+        ),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        storage.object_keys(),
+        vec!["renders/v1.mp4", "renders/v2.mp4"]
+    );
+    assert_eq!(
+        audit.entries.lock().unwrap()[0].outcome,
+        AuditOutcome::DryRun
+    );
+}
+
+#[tokio::test]
+async fn test_copy_object_dry_run_is_audited_as_such() {
+    let storage = renders();
+    let audit = MockAuditLog::default();
+
+    let response = send(
+        admin_app(storage.clone(), audit.clone()),
+        admin_request(
+            Method::POST,
+            "/api/v1/video/copy",
+            Some(json!({
+                "source_key": "renders/v1.mp4",
+                "destination_key": "archive/v1.mp4",
+                "dry_run": true
+            })),
+        ),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await["dry_run"], true);
+    assert_eq!(storage.object_keys().len(), 2);
+
+    let entries = audit.entries.lock().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].action, "copy");
+    assert!(entries[0].dry_run);
+    assert_eq!(entries[0].outcome, AuditOutcome::DryRun);
+}
+
+#[tokio::test]
+async fn test_object_management_requires_admin() {
+    let storage = renders();
+    let request = Request::builder()
+        .method(Method::DELETE)
+        .uri("/api/v1/video/object?object_key=renders/v1.mp4")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = send(admin_app(storage.clone(), MockAuditLog::default()), request).await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(storage.object_keys().len(), 2);
+}